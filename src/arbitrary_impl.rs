@@ -0,0 +1,100 @@
+// Copyright 2015, The inlinable_string crate Developers. See the COPYRIGHT file
+// at the top-level directory of this distribution.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license <LICENSE-MIT
+// or http://opensource.org/licenses/MIT>, at your option. This file may not be
+// copied, modified, or distributed except according to those terms.
+
+use crate::{InlinableString, InlineString};
+use alloc::string::String;
+use arbitrary::{size_hint, Arbitrary, Result, Unstructured};
+
+/// Generates both variants: a leading flag decides whether the contents are
+/// stored inline when they fit, or always on the heap, so short heap strings
+/// (such as those left behind by `truncate`) are covered too.
+impl<'a> Arbitrary<'a> for InlinableString {
+    fn arbitrary(u: &mut Unstructured<'a>) -> Result<InlinableString> {
+        let heap = bool::arbitrary(u)?;
+        let contents = <&str>::arbitrary(u)?;
+        Ok(with_storage(contents, heap))
+    }
+
+    fn arbitrary_take_rest(mut u: Unstructured<'a>) -> Result<InlinableString> {
+        let heap = bool::arbitrary(&mut u)?;
+        let contents = <&str>::arbitrary_take_rest(u)?;
+        Ok(with_storage(contents, heap))
+    }
+
+    #[inline]
+    fn size_hint(depth: usize) -> (usize, Option<usize>) {
+        size_hint::and(bool::size_hint(depth), <&str>::size_hint(depth))
+    }
+}
+
+fn with_storage(contents: &str, heap: bool) -> InlinableString {
+    if heap {
+        InlinableString::Heap(String::from(contents))
+    } else {
+        InlinableString::from(contents)
+    }
+}
+
+/// Generates a string from as much of an arbitrary `&str` as fits inline,
+/// cut at a character boundary.
+impl<'a> Arbitrary<'a> for InlineString {
+    fn arbitrary(u: &mut Unstructured<'a>) -> Result<InlineString> {
+        let mut string = InlineString::new();
+        string.push_str_truncating(<&str>::arbitrary(u)?);
+        Ok(string)
+    }
+
+    fn arbitrary_take_rest(u: Unstructured<'a>) -> Result<InlineString> {
+        let mut string = InlineString::new();
+        string.push_str_truncating(<&str>::arbitrary_take_rest(u)?);
+        Ok(string)
+    }
+
+    #[inline]
+    fn size_hint(depth: usize) -> (usize, Option<usize>) {
+        <&str>::size_hint(depth)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{InlinableString, InlineString};
+    use alloc::vec::Vec;
+    use arbitrary::{Arbitrary, Unstructured};
+
+    #[test]
+    fn test_arbitrary_variants() {
+        let data: Vec<u8> = (0..=255).cycle().take(4096).collect();
+        let mut u = Unstructured::new(&data);
+        let (mut inline, mut heap, mut short_heap) = (0, 0, 0);
+        while !u.is_empty() {
+            match InlinableString::arbitrary(&mut u).unwrap() {
+                InlinableString::Inline(_) => inline += 1,
+                InlinableString::Heap(s) => {
+                    heap += 1;
+                    if s.len() <= InlineString::CAPACITY {
+                        short_heap += 1;
+                    }
+                }
+            }
+        }
+        assert!(inline > 0);
+        assert!(heap > 0);
+        assert!(short_heap > 0);
+    }
+
+    #[test]
+    fn test_arbitrary_inline_string() {
+        let data = [b'x'; 4 * InlineString::CAPACITY];
+        let s = InlineString::arbitrary_take_rest(Unstructured::new(&data)).unwrap();
+        assert_eq!(s.len(), InlineString::CAPACITY);
+
+        let s = InlineString::arbitrary_take_rest(Unstructured::new("añb".as_bytes())).unwrap();
+        assert_eq!(s, "añb");
+    }
+}
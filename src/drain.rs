@@ -0,0 +1,234 @@
+// Copyright 2015, The inlinable_string crate Developers. See the COPYRIGHT file
+// at the top-level directory of this distribution.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license <LICENSE-MIT
+// or http://opensource.org/licenses/MIT>, at your option. This file may not be
+// copied, modified, or distributed except according to those terms.
+
+//! The `Drain` iterator returned by `StringExt::drain` and
+//! `InlineString::drain`.
+//!
+//! Draining a heap-allocated string wraps `std::string::Drain`. Draining an
+//! inline string moves the removed characters into a small inline buffer up
+//! front, so the iterator never needs to allocate.
+//!
+//! # Examples
+//!
+//! ```
+//! use inlinable_string::{InlinableString, StringExt};
+//!
+//! let mut s = InlinableString::from("α is alpha, β is beta");
+//! let beta_offset = s.find('β').unwrap();
+//!
+//! let removed: String = s.drain(..beta_offset).collect();
+//! assert_eq!(removed, "α is alpha, ");
+//! assert_eq!(s, "β is beta");
+//! ```
+
+use crate::InlinableString;
+use alloc::string;
+use core::fmt;
+use core::iter::FusedIterator;
+use core::ops::{Bound, Range, RangeBounds};
+
+/// A draining iterator over the characters removed from a string.
+///
+/// Created by `StringExt::drain` and `InlineString::drain`. See the
+/// [module level documentation](./index.html) for more.
+pub struct Drain<'a> {
+    inner: Inner<'a>,
+}
+
+enum Inner<'a> {
+    Heap(string::Drain<'a>),
+    /// The removed characters, already taken out of the source string, and
+    /// the byte range of them not yet yielded.
+    Owned {
+        removed: InlinableString,
+        remaining: Range<usize>,
+    },
+}
+
+impl<'a> Drain<'a> {
+    pub(crate) fn heap(drain: string::Drain<'a>) -> Drain<'a> {
+        Drain {
+            inner: Inner::Heap(drain),
+        }
+    }
+
+    pub(crate) fn owned(removed: InlinableString) -> Drain<'a> {
+        let remaining = 0..removed.len();
+        Drain {
+            inner: Inner::Owned { removed, remaining },
+        }
+    }
+
+    /// Returns the characters not yet yielded by this iterator as a string
+    /// slice.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use inlinable_string::{InlinableString, StringExt};
+    ///
+    /// let mut s = InlinableString::from("abc");
+    /// let mut drain = s.drain(..);
+    /// assert_eq!(drain.as_str(), "abc");
+    /// drain.next();
+    /// assert_eq!(drain.as_str(), "bc");
+    /// ```
+    pub fn as_str(&self) -> &str {
+        match self.inner {
+            Inner::Heap(ref drain) => drain.as_str(),
+            Inner::Owned {
+                ref removed,
+                ref remaining,
+            } => &removed[remaining.clone()],
+        }
+    }
+}
+
+impl<'a> Iterator for Drain<'a> {
+    type Item = char;
+
+    #[inline]
+    fn next(&mut self) -> Option<char> {
+        match self.inner {
+            Inner::Heap(ref mut drain) => drain.next(),
+            Inner::Owned {
+                ref removed,
+                ref mut remaining,
+            } => {
+                let ch = removed[remaining.clone()].chars().next()?;
+                remaining.start += ch.len_utf8();
+                Some(ch)
+            }
+        }
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        match self.inner {
+            Inner::Heap(ref drain) => drain.size_hint(),
+            Inner::Owned {
+                ref removed,
+                ref remaining,
+            } => removed[remaining.clone()].chars().size_hint(),
+        }
+    }
+}
+
+impl<'a> DoubleEndedIterator for Drain<'a> {
+    #[inline]
+    fn next_back(&mut self) -> Option<char> {
+        match self.inner {
+            Inner::Heap(ref mut drain) => drain.next_back(),
+            Inner::Owned {
+                ref removed,
+                ref mut remaining,
+            } => {
+                let ch = removed[remaining.clone()].chars().next_back()?;
+                remaining.end -= ch.len_utf8();
+                Some(ch)
+            }
+        }
+    }
+}
+
+impl<'a> FusedIterator for Drain<'a> {}
+
+impl<'a> fmt::Debug for Drain<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("Drain").field(&self.as_str()).finish()
+    }
+}
+
+/// Resolves `range` against `string`, panicking like `String::drain` if it is
+/// out of bounds or does not lie on character boundaries.
+pub(crate) fn resolve_range<R: RangeBounds<usize>>(string: &str, range: R) -> Range<usize> {
+    let start = match range.start_bound() {
+        Bound::Included(&n) => n,
+        Bound::Excluded(&n) => n
+            .checked_add(1)
+            .expect("inlinable_string: range start overflows usize"),
+        Bound::Unbounded => 0,
+    };
+    let end = match range.end_bound() {
+        Bound::Included(&n) => n
+            .checked_add(1)
+            .expect("inlinable_string: range end overflows usize"),
+        Bound::Excluded(&n) => n,
+        Bound::Unbounded => string.len(),
+    };
+    assert!(
+        start <= end,
+        "inlinable_string: range start is greater than range end"
+    );
+    assert!(
+        end <= string.len(),
+        "inlinable_string: range end is out of bounds"
+    );
+    assert!(
+        string.is_char_boundary(start) && string.is_char_boundary(end),
+        "inlinable_string: range does not lie on character boundaries"
+    );
+    start..end
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{InlinableString, InlineString, StringExt};
+    use alloc::string::String;
+
+    #[test]
+    fn test_drain_inline() {
+        let mut s = InlinableString::from("añb€c");
+        {
+            let mut drain = s.drain(1..=6);
+            assert_eq!(drain.next_back(), Some('€'));
+            assert_eq!(drain.as_str(), "ñb");
+            assert_eq!(drain.next(), Some('ñ'));
+        }
+        assert_eq!(s, "ac");
+        assert!(matches!(s, InlinableString::Inline(_)));
+    }
+
+    #[test]
+    fn test_drain_heap() {
+        let mut s = InlinableString::from("a string that is long enough to be on the heap");
+        let removed: String = s.drain(..9).collect();
+        assert_eq!(removed, "a string ");
+        assert_eq!(s, "that is long enough to be on the heap");
+
+        // Dropping the iterator without consuming it still removes the range.
+        s.drain(4..);
+        assert_eq!(s, "that");
+    }
+
+    #[test]
+    fn test_drain_string() {
+        let mut s = String::from("hello world");
+        let removed: String = StringExt::drain(&mut s, 5..).rev().collect();
+        assert_eq!(removed, "dlrow ");
+        assert_eq!(s, "hello");
+    }
+
+    #[test]
+    fn test_drain_inline_string() {
+        let mut s = InlineString::from("añb€c");
+        let removed: String = s.drain(1..=6).rev().collect();
+        assert_eq!(removed, "€bñ");
+        assert_eq!(s, "ac");
+
+        s.drain(..);
+        assert_eq!(s, "");
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_drain_not_char_boundary() {
+        let mut s = InlinableString::from("é");
+        s.drain(1..);
+    }
+}
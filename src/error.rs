@@ -0,0 +1,165 @@
+// Copyright 2015, The inlinable_string crate Developers. See the COPYRIGHT file
+// at the top-level directory of this distribution.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license <LICENSE-MIT
+// or http://opensource.org/licenses/MIT>, at your option. This file may not be
+// copied, modified, or distributed except according to those terms.
+
+//! A crate-level error type that unifies the specific failure modes of this
+//! crate's fallible APIs.
+//!
+//! Every specific error type converts into `Error` with `From`, so code that
+//! mixes several fallible operations can bubble them up with `?`.
+//!
+//! # Examples
+//!
+//! ```
+//! use inlinable_string::{Error, InlineString, InlinableString, StringExt};
+//!
+//! fn build(bytes: Vec<u8>) -> Result<InlineString, Error> {
+//!     let s = InlinableString::from_utf8(bytes)?;
+//!     let mut inline = InlineString::new();
+//!     inline.push_str(&s)?;
+//!     Ok(inline)
+//! }
+//!
+//! assert_eq!(build(b"hello".to_vec()).unwrap(), "hello");
+//! assert!(matches!(build(vec![0xff]), Err(Error::FromUtf8(_))));
+//! ```
+
+use crate::inline_string::NotEnoughSpaceError;
+use alloc::collections::TryReserveError;
+use alloc::string::{FromUtf16Error, FromUtf8Error};
+use core::fmt;
+use core::str::Utf8Error;
+
+/// The error type for the fallible operations in this crate.
+///
+/// Each variant displays a short message of its own and reports the error
+/// it wraps as its `source`, so error-chain reporters print the details once.
+///
+/// See the [module level documentation](./index.html) for more.
+#[derive(Debug)]
+#[non_exhaustive]
+pub enum Error {
+    /// There was not enough space in an `InlineString` for the requested
+    /// operation.
+    NotEnoughSpace(NotEnoughSpaceError),
+    /// A byte slice was not valid UTF-8.
+    Utf8(Utf8Error),
+    /// A byte vector was not valid UTF-8.
+    FromUtf8(FromUtf8Error),
+    /// A UTF-16 slice contained invalid data.
+    FromUtf16(FromUtf16Error),
+    /// Reserving capacity failed.
+    TryReserve(TryReserveError),
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match *self {
+            Error::NotEnoughSpace(_) => f.write_str("not enough space in inline string"),
+            Error::Utf8(_) => f.write_str("byte slice is not valid UTF-8"),
+            Error::FromUtf8(_) => f.write_str("byte vector is not valid UTF-8"),
+            Error::FromUtf16(_) => f.write_str("invalid UTF-16"),
+            Error::TryReserve(_) => f.write_str("failed to reserve capacity"),
+        }
+    }
+}
+
+#[cfg(not(feature = "no_std"))]
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match *self {
+            Error::NotEnoughSpace(ref e) => Some(e),
+            Error::Utf8(ref e) => Some(e),
+            Error::FromUtf8(ref e) => Some(e),
+            Error::FromUtf16(ref e) => Some(e),
+            Error::TryReserve(ref e) => Some(e),
+        }
+    }
+}
+
+impl From<NotEnoughSpaceError> for Error {
+    #[inline]
+    fn from(error: NotEnoughSpaceError) -> Error {
+        Error::NotEnoughSpace(error)
+    }
+}
+
+impl From<Utf8Error> for Error {
+    #[inline]
+    fn from(error: Utf8Error) -> Error {
+        Error::Utf8(error)
+    }
+}
+
+impl From<FromUtf8Error> for Error {
+    #[inline]
+    fn from(error: FromUtf8Error) -> Error {
+        Error::FromUtf8(error)
+    }
+}
+
+impl From<FromUtf16Error> for Error {
+    #[inline]
+    fn from(error: FromUtf16Error) -> Error {
+        Error::FromUtf16(error)
+    }
+}
+
+impl From<TryReserveError> for Error {
+    #[inline]
+    fn from(error: TryReserveError) -> Error {
+        Error::TryReserve(error)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Error;
+    use crate::inline_string::NotEnoughSpaceError;
+    use alloc::string::{String, ToString};
+    use alloc::vec::Vec;
+
+    #[test]
+    fn test_from() {
+        let invalid = vec![0xff];
+
+        let e: Error = NotEnoughSpaceError::new(41, 30).into();
+        assert!(matches!(e, Error::NotEnoughSpace(_)));
+        assert_eq!(e.to_string(), "not enough space in inline string");
+
+        let e: Error = String::from_utf8(invalid.clone()).unwrap_err().into();
+        assert!(matches!(e, Error::FromUtf8(_)));
+        assert_eq!(e.to_string(), "byte vector is not valid UTF-8");
+
+        let e: Error = core::str::from_utf8(&invalid).unwrap_err().into();
+        assert!(matches!(e, Error::Utf8(_)));
+
+        let e: Error = String::from_utf16(&[0xD800]).unwrap_err().into();
+        assert!(matches!(e, Error::FromUtf16(_)));
+
+        let e: Error = Vec::<u8>::new().try_reserve(usize::MAX).unwrap_err().into();
+        assert!(matches!(e, Error::TryReserve(_)));
+    }
+
+    #[cfg(not(feature = "no_std"))]
+    #[test]
+    fn test_source() {
+        use std::error::Error as StdError;
+
+        let e: Error = NotEnoughSpaceError::new(41, 30).into();
+        assert_eq!(
+            e.source().unwrap().to_string(),
+            "needed 41 bytes, but the capacity is 30"
+        );
+
+        let invalid = vec![0xff];
+        let utf8 = core::str::from_utf8(&invalid).unwrap_err();
+        let e: Error = utf8.into();
+        assert_eq!(e.source().unwrap().to_string(), utf8.to_string());
+        assert_ne!(e.to_string(), utf8.to_string());
+    }
+}
@@ -7,7 +7,7 @@
 // copied, modified, or distributed except according to those terms.
 
 //! A short UTF-8 string that uses inline storage and does no heap
-//! allocation. It may be no longer than `INLINE_STRING_CAPACITY` bytes long.
+//! allocation. It may be no longer than `InlineString::CAPACITY` bytes long.
 //!
 //! The capacity restriction makes many operations that would otherwise be
 //! infallible on `std::string::String` fallible. Additionally, many trait
@@ -30,41 +30,118 @@
 //! assert!(s.push_str("hi world").is_ok());
 //! assert_eq!(s, "hi world");
 //!
-//! assert!(s.push_str("a really long string that is much bigger than `INLINE_STRING_CAPACITY`").is_err());
+//! assert!(s.push_str("a really long string that is much bigger than `InlineString::CAPACITY`").is_err());
 //! assert_eq!(s, "hi world");
 //! ```
 
 use alloc::borrow;
+use alloc::string::String;
+use core::cmp::Ordering;
+use core::convert::TryFrom;
 use core::fmt;
 use core::hash;
 use core::ops;
 use core::ptr;
 use core::str;
+use crate::drain::{self, Drain};
+use crate::spare::SpareCapacity;
+use crate::{Error, InlinableString};
 
 
-/// The capacity (in bytes) of inline storage for small strings.
-/// `InlineString::len()` may never be larger than this.
-///
-/// Sometime in the future, when Rust's generics support specializing with
-/// compile-time static integers, this number should become configurable.
+/// The capacity (in bytes) of inline storage for small strings. Equal to
+/// `InlineString::CAPACITY`.
+#[deprecated(note = "use `InlineString::CAPACITY` instead")]
+pub const INLINE_STRING_CAPACITY: usize = InlineString::CAPACITY;
+
+// The value of `InlineString::CAPACITY`: with the length byte and the
+// `InlinableString` discriminant, it fills four words on each target.
 #[cfg(target_pointer_width = "64")]
-pub const INLINE_STRING_CAPACITY: usize = 30;
+const TARGET_CAPACITY: usize = 30;
 #[cfg(target_pointer_width = "32")]
-pub const INLINE_STRING_CAPACITY: usize = 14;
+const TARGET_CAPACITY: usize = 14;
+
+// `InlineString` stores its length in a `u8`, so every length up to the
+// capacity must be representable in one.
+const _: () = assert!(InlineString::CAPACITY <= u8::MAX as usize);
 
 /// A short UTF-8 string that uses inline storage and does no heap allocation.
 ///
+/// # Layout
+///
+/// An `InlineString` is laid out as a single length byte followed by
+/// `InlineString::CAPACITY` content bytes, with no padding and an alignment
+/// of one. The first `length` content bytes are valid UTF-8, and every content
+/// byte past `length` is zero. With the `zerocopy` feature, this layout is
+/// exposed through `zerocopy`'s `IntoBytes`, `Immutable`, `KnownLayout` and
+/// `Unaligned` traits.
+///
 /// See the [module level documentation](./index.html) for more.
 #[derive(Clone, Debug, Eq)]
+#[cfg_attr(
+    feature = "zerocopy",
+    derive(
+        zerocopy::IntoBytes,
+        zerocopy::Immutable,
+        zerocopy::KnownLayout,
+        zerocopy::Unaligned
+    )
+)]
+#[repr(C)]
 pub struct InlineString {
     length: u8,
-    bytes: [u8; INLINE_STRING_CAPACITY],
+    bytes: [u8; InlineString::CAPACITY],
 }
 
 /// The error returned when there is not enough space in a `InlineString` for the
 /// requested operation.
-#[derive(Debug, PartialEq)]
-pub struct NotEnoughSpaceError;
+///
+/// # Examples
+///
+/// ```
+/// use inlinable_string::InlineString;
+///
+/// let mut s = InlineString::from("abc");
+/// let error = s.push_str(&"x".repeat(InlineString::CAPACITY)).unwrap_err();
+/// assert_eq!(error.needed(), InlineString::CAPACITY + 3);
+/// assert_eq!(error.capacity(), InlineString::CAPACITY);
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct NotEnoughSpaceError {
+    needed: usize,
+    capacity: usize,
+}
+
+impl NotEnoughSpaceError {
+    #[inline]
+    pub(crate) fn new(needed: usize, capacity: usize) -> NotEnoughSpaceError {
+        NotEnoughSpaceError { needed, capacity }
+    }
+
+    /// Returns the length, in bytes, that the operation needed room for.
+    #[inline]
+    pub fn needed(&self) -> usize {
+        self.needed
+    }
+
+    /// Returns the capacity, in bytes, that was available.
+    #[inline]
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+}
+
+impl fmt::Display for NotEnoughSpaceError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "needed {} bytes, but the capacity is {}",
+            self.needed, self.capacity
+        )
+    }
+}
+
+#[cfg(not(feature = "no_std"))]
+impl std::error::Error for NotEnoughSpaceError {}
 
 impl AsRef<str> for InlineString {
     fn as_ref(&self) -> &str {
@@ -97,32 +174,166 @@ impl AsMut<[u8]> for InlineString {
     }
 }
 
+/// A byte-oriented predicate for `retain_bytes`.
+///
+/// Implemented for every `FnMut(u8) -> bool`. The predicate is only ever asked
+/// about ASCII bytes, so it can be written as a simple byte test (or a lookup
+/// table) without worrying about splitting multi-byte characters.
+pub trait AsciiPredicate {
+    /// Returns true if the ASCII byte `byte` should be kept.
+    fn keep(&mut self, byte: u8) -> bool;
+}
+
+impl<F: FnMut(u8) -> bool> AsciiPredicate for F {
+    #[inline]
+    fn keep(&mut self, byte: u8) -> bool {
+        self(byte)
+    }
+}
+
+/// An item that `InlineString::try_from_iter` and `InlineString::try_extend`
+/// can append to a string.
+///
+/// Implemented for `char`, `&char` and `&str`.
+pub trait InlinePushable {
+    /// Appends this item to `string`, leaving `string` unchanged if the item
+    /// does not fit.
+    fn try_push_onto(self, string: &mut InlineString) -> Result<(), NotEnoughSpaceError>;
+}
+
+impl InlinePushable for char {
+    #[inline]
+    fn try_push_onto(self, string: &mut InlineString) -> Result<(), NotEnoughSpaceError> {
+        string.push(self)
+    }
+}
+
+impl InlinePushable for &char {
+    #[inline]
+    fn try_push_onto(self, string: &mut InlineString) -> Result<(), NotEnoughSpaceError> {
+        string.push(*self)
+    }
+}
+
+impl InlinePushable for &str {
+    #[inline]
+    fn try_push_onto(self, string: &mut InlineString) -> Result<(), NotEnoughSpaceError> {
+        string.push_str(self)
+    }
+}
+
 /// Create a `InlineString` from the given `&str`.
 ///
 /// # Panics
 ///
-/// If the given string's size is greater than `INLINE_STRING_CAPACITY`, this
+/// If the given string's size is greater than `InlineString::CAPACITY`, this
 /// method panics.
 impl<'a> From<&'a str> for InlineString {
     fn from(string: &'a str) -> InlineString {
         let string_len = string.len();
-        assert!(string_len <= INLINE_STRING_CAPACITY);
+        assert!(string_len <= InlineString::CAPACITY);
 
         let mut ss = InlineString::new();
         unsafe {
             ptr::copy_nonoverlapping(string.as_ptr(), ss.bytes.as_mut_ptr(), string_len);
         }
-        ss.length = string_len as u8;
+        ss.set_length(string_len);
 
         ss.assert_sanity();
         ss
     }
 }
 
+/// Copies the string into inline storage, failing if it is longer than
+/// `InlineString::CAPACITY`.
+impl<'a> TryFrom<&'a String> for InlineString {
+    type Error = NotEnoughSpaceError;
+
+    #[inline]
+    fn try_from(string: &'a String) -> Result<InlineString, NotEnoughSpaceError> {
+        let mut inline = InlineString::new();
+        inline.push_str(string)?;
+        Ok(inline)
+    }
+}
+
+/// Copies the string into inline storage, failing if it is longer than
+/// `InlineString::CAPACITY`.
+impl TryFrom<String> for InlineString {
+    type Error = NotEnoughSpaceError;
+
+    #[inline]
+    fn try_from(string: String) -> Result<InlineString, NotEnoughSpaceError> {
+        InlineString::try_from(&string)
+    }
+}
+
+/// Creates a single-character string. Every `char` fits in inline storage,
+/// so this never fails in practice; the fallible signature matches the other
+/// conversions into `InlineString`.
+impl TryFrom<char> for InlineString {
+    type Error = NotEnoughSpaceError;
+
+    #[inline]
+    fn try_from(ch: char) -> Result<InlineString, NotEnoughSpaceError> {
+        let mut inline = InlineString::new();
+        inline.push(ch)?;
+        Ok(inline)
+    }
+}
+
+/// Validates the bytes as UTF-8 and copies them into inline storage.
+///
+/// Fails with `Error::Utf8` if the bytes are not valid UTF-8, and with
+/// `Error::NotEnoughSpace` if they are longer than `InlineString::CAPACITY`.
+///
+/// # Examples
+///
+/// ```
+/// use inlinable_string::{Error, InlineString};
+/// use std::convert::TryFrom;
+///
+/// assert_eq!(InlineString::try_from(&b"token"[..]).unwrap(), "token");
+/// assert!(matches!(InlineString::try_from(&b"\xff"[..]), Err(Error::Utf8(_))));
+/// ```
+impl<'a> TryFrom<&'a [u8]> for InlineString {
+    type Error = Error;
+
+    #[inline]
+    fn try_from(bytes: &'a [u8]) -> Result<InlineString, Error> {
+        let string = str::from_utf8(bytes)?;
+        let mut inline = InlineString::new();
+        inline.push_str(string)?;
+        Ok(inline)
+    }
+}
+
+/// Copies the string into a zero-padded fixed-size array; see
+/// `InlineString::to_fixed_bytes`.
+impl<'a, const N: usize> TryFrom<&'a InlineString> for [u8; N] {
+    type Error = NotEnoughSpaceError;
+
+    #[inline]
+    fn try_from(string: &'a InlineString) -> Result<[u8; N], NotEnoughSpaceError> {
+        string.to_fixed_bytes()
+    }
+}
+
+/// Copies the string into a zero-padded fixed-size array; see
+/// `InlineString::to_fixed_bytes`.
+impl<const N: usize> TryFrom<InlineString> for [u8; N] {
+    type Error = NotEnoughSpaceError;
+
+    #[inline]
+    fn try_from(string: InlineString) -> Result<[u8; N], NotEnoughSpaceError> {
+        string.to_fixed_bytes()
+    }
+}
+
 impl fmt::Display for InlineString {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> Result<(), fmt::Error> {
         self.assert_sanity();
-        write!(f, "{}", self as &str)
+        f.pad(self)
     }
 }
 
@@ -135,6 +346,20 @@ impl fmt::Write for InlineString {
     }
 }
 
+impl borrow::Borrow<str> for InlineString {
+    #[inline]
+    fn borrow(&self) -> &str {
+        self
+    }
+}
+
+impl borrow::BorrowMut<str> for InlineString {
+    #[inline]
+    fn borrow_mut(&mut self) -> &mut str {
+        self
+    }
+}
+
 impl hash::Hash for InlineString {
     #[inline]
     fn hash<H: hash::Hasher>(&self, hasher: &mut H) {
@@ -246,7 +471,11 @@ impl PartialEq<InlineString> for InlineString {
     fn eq(&self, rhs: &InlineString) -> bool {
         self.assert_sanity();
         rhs.assert_sanity();
-        PartialEq::eq(&self[..], &rhs[..])
+        // The bytes past the length are always zeroed, so comparing the whole
+        // fixed-size buffers is equivalent to comparing the strings, and
+        // compiles down to a few word-sized comparisons without a
+        // length-dependent loop.
+        self.length == rhs.length && self.bytes == rhs.bytes
     }
 }
 
@@ -272,18 +501,98 @@ impl_eq! { InlineString, str }
 impl_eq! { InlineString, &'a str }
 impl_eq! { borrow::Cow<'a, str>, InlineString }
 
+impl PartialOrd<InlineString> for InlineString {
+    #[inline]
+    fn partial_cmp(&self, rhs: &InlineString) -> Option<Ordering> {
+        Some(Ord::cmp(self, rhs))
+    }
+}
+
+impl Ord for InlineString {
+    #[inline]
+    fn cmp(&self, rhs: &InlineString) -> Ordering {
+        Ord::cmp(&self[..], &rhs[..])
+    }
+}
+
+macro_rules! impl_ord {
+    ($lhs:ty, $rhs: ty) => {
+        impl<'a> PartialOrd<$rhs> for $lhs {
+            #[inline]
+            fn partial_cmp(&self, other: &$rhs) -> Option<Ordering> {
+                PartialOrd::partial_cmp(&self[..], &other[..])
+            }
+        }
+
+        impl<'a> PartialOrd<$lhs> for $rhs {
+            #[inline]
+            fn partial_cmp(&self, other: &$lhs) -> Option<Ordering> {
+                PartialOrd::partial_cmp(&self[..], &other[..])
+            }
+        }
+    };
+}
+
+impl_ord! { InlineString, str }
+impl_ord! { InlineString, &'a str }
+impl_ord! { borrow::Cow<'a, str>, InlineString }
+
 impl InlineString {
-    #[cfg_attr(feature = "nightly", allow(inline_always))]
+    /// The capacity (in bytes) of inline storage for small strings.
+    /// `InlineString::len()` may never be larger than this.
+    ///
+    /// This is the canonical definition; `InlinableString::INLINE_CAPACITY`
+    /// and the deprecated `INLINE_STRING_CAPACITY` are both defined in terms
+    /// of it. It is 30 on 64-bit targets and 14 on 32-bit targets, which
+    /// makes an `InlinableString` exactly four words long.
+    ///
+    /// Sometime in the future, when Rust's generics support specializing with
+    /// compile-time static integers, this number should become configurable.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use inlinable_string::InlineString;
+    ///
+    /// let mut s = InlineString::from("x".repeat(InlineString::CAPACITY).as_str());
+    /// assert!(s.push('y').is_err());
+    /// ```
+    pub const CAPACITY: usize = TARGET_CAPACITY;
+
+    /// Sets the stored length, checking (in debug builds) that it is within
+    /// capacity, and zeroes any bytes vacated by shrinking. All writes to
+    /// `self.length` go through here.
+    #[cfg_attr(feature = "nightly", allow(clippy::inline_always))]
+    #[inline(always)]
+    fn set_length(&mut self, new_len: usize) {
+        debug_assert!(
+            new_len <= InlineString::CAPACITY,
+            "inlinable_string: internal error: length greater than capacity"
+        );
+        let old_len = self.length as usize;
+        if new_len < old_len {
+            self.bytes[new_len..old_len].fill(0);
+        }
+        // Cannot truncate: `InlineString::CAPACITY <= u8::MAX` is asserted at
+        // compile time above.
+        self.length = new_len as u8;
+    }
+
+    #[cfg_attr(feature = "nightly", allow(clippy::inline_always))]
     #[inline(always)]
     fn assert_sanity(&self) {
         debug_assert!(
-            self.length as usize <= INLINE_STRING_CAPACITY,
+            self.length as usize <= InlineString::CAPACITY,
             "inlinable_string: internal error: length greater than capacity"
         );
         debug_assert!(
             str::from_utf8(&self.bytes[0..self.length as usize]).is_ok(),
             "inlinable_string: internal error: contents are not valid UTF-8!"
         );
+        debug_assert!(
+            self.bytes[self.length as usize..].iter().all(|&b| b == 0),
+            "inlinable_string: internal error: bytes past the length are not zeroed"
+        );
     }
 
     /// Creates a new string buffer initialized with the empty string.
@@ -294,15 +603,135 @@ impl InlineString {
     /// use inlinable_string::InlineString;
     ///
     /// let s = InlineString::new();
+    ///
+    /// // `new` is a `const fn`, so it can initialize statics.
+    /// static EMPTY: InlineString = InlineString::new();
+    /// assert!(EMPTY.is_empty());
     /// ```
     #[inline]
-    pub fn new() -> InlineString {
+    pub const fn new() -> InlineString {
         InlineString {
             length: 0,
-            bytes: [0; INLINE_STRING_CAPACITY],
+            bytes: [0; InlineString::CAPACITY],
         }
     }
 
+    /// Creates a string from an iterator of characters or string slices,
+    /// failing if they do not all fit.
+    ///
+    /// `FromIterator` cannot report running out of space, so `InlineString`
+    /// does not implement it; this is the fallible equivalent.
+    ///
+    /// # Errors
+    ///
+    /// Stops at the first item that does not fit, returning the string built
+    /// from the items before it along with the error. Items after it are not
+    /// consumed.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use inlinable_string::InlineString;
+    ///
+    /// let s = InlineString::try_from_iter("hello".chars().rev()).unwrap();
+    /// assert_eq!(s, "olleh");
+    ///
+    /// let words = std::iter::repeat("word ").take(10);
+    /// let (partial, error) = InlineString::try_from_iter(words).unwrap_err();
+    /// assert!(partial.starts_with("word word "));
+    /// assert_eq!(error.needed(), partial.len() + 5);
+    /// ```
+    pub fn try_from_iter<I>(iter: I) -> Result<InlineString, (InlineString, NotEnoughSpaceError)>
+    where
+        I: IntoIterator,
+        I::Item: InlinePushable,
+    {
+        let mut string = InlineString::new();
+        match string.try_extend(iter) {
+            Ok(()) => Ok(string),
+            Err(error) => Err((string, error)),
+        }
+    }
+
+    /// Creates a string holding a copy of the given string literal.
+    ///
+    /// This is a `const fn`, so it can build statics and constant lookup
+    /// tables.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `string` is longer than `InlineString::CAPACITY` bytes. In a
+    /// constant context, this is a compile-time error.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use inlinable_string::InlineString;
+    ///
+    /// static UNITS: [InlineString; 3] = [
+    ///     InlineString::from_static("B"),
+    ///     InlineString::from_static("KiB"),
+    ///     InlineString::from_static("MiB"),
+    /// ];
+    /// assert_eq!(UNITS[1], "KiB");
+    /// ```
+    pub const fn from_static(string: &'static str) -> InlineString {
+        let src = string.as_bytes();
+        if src.len() > InlineString::CAPACITY {
+            panic!("inlinable_string: string literal is too long to be stored inline");
+        }
+        let mut bytes = [0; InlineString::CAPACITY];
+        let mut i = 0;
+        while i < src.len() {
+            bytes[i] = src[i];
+            i += 1;
+        }
+        InlineString {
+            length: src.len() as u8,
+            bytes,
+        }
+    }
+
+    /// Creates a string from a byte buffer holding `len` bytes of UTF-8,
+    /// adopting the buffer rather than copying through a `&str`. Any bytes
+    /// past `len` are zeroed. This is the inverse of `into_bytes`.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::NotEnoughSpace` if `len` is greater than
+    /// `InlineString::CAPACITY`, and `Error::Utf8` if the first `len` bytes
+    /// are not valid UTF-8.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use inlinable_string::InlineString;
+    ///
+    /// let mut record = [b'!'; InlineString::CAPACITY];
+    /// record[..4].copy_from_slice(b"name");
+    /// let s = InlineString::from_utf8_array(record, 4).unwrap();
+    /// assert_eq!(s, "name");
+    /// assert_eq!(InlineString::from_utf8_array(s.clone().into_bytes(), s.len()).unwrap(), s);
+    /// ```
+    pub fn from_utf8_array(
+        mut bytes: [u8; InlineString::CAPACITY],
+        len: usize,
+    ) -> Result<InlineString, Error> {
+        if len > InlineString::CAPACITY {
+            return Err(NotEnoughSpaceError::new(len, InlineString::CAPACITY).into());
+        }
+        str::from_utf8(&bytes[..len])?;
+        for byte in &mut bytes[len..] {
+            *byte = 0;
+        }
+        let string = InlineString {
+            length: len as u8,
+            bytes,
+        };
+        string.assert_sanity();
+        Ok(string)
+    }
+
     /// Returns the underlying byte buffer, encoded as UTF-8. Trailing bytes are
     /// zeroed.
     ///
@@ -316,14 +745,81 @@ impl InlineString {
     /// assert_eq!(&bytes[0..5], [104, 101, 108, 108, 111]);
     /// ```
     #[inline]
-    pub fn into_bytes(mut self) -> [u8; INLINE_STRING_CAPACITY] {
+    pub fn into_bytes(self) -> [u8; InlineString::CAPACITY] {
         self.assert_sanity();
-        for i in self.len()..INLINE_STRING_CAPACITY {
-            self.bytes[i] = 0;
-        }
         self.bytes
     }
 
+    /// Returns the underlying byte buffer together with the length of the
+    /// string in it. Trailing bytes are zeroed.
+    ///
+    /// This is the inverse of `from_utf8_array`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use inlinable_string::InlineString;
+    ///
+    /// let (bytes, len) = InlineString::from("hello").into_array();
+    /// assert_eq!(&bytes[..len], b"hello");
+    /// assert!(bytes[len..].iter().all(|&b| b == 0));
+    /// assert_eq!(InlineString::from_utf8_array(bytes, len).unwrap(), "hello");
+    /// ```
+    #[inline]
+    pub fn into_array(self) -> ([u8; InlineString::CAPACITY], usize) {
+        let len = self.len();
+        (self.into_bytes(), len)
+    }
+
+    /// Borrows the whole underlying byte buffer, encoded as UTF-8. The first
+    /// `self.len()` bytes are the string, and the rest are zeroed.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use inlinable_string::InlineString;
+    ///
+    /// let s = InlineString::from("hi");
+    /// let record = s.as_array();
+    /// assert_eq!(record.len(), InlineString::CAPACITY);
+    /// assert_eq!(&record[..s.len()], b"hi");
+    /// assert_eq!(record[s.len()], 0);
+    /// ```
+    #[inline]
+    pub fn as_array(&self) -> &[u8; InlineString::CAPACITY] {
+        self.assert_sanity();
+        &self.bytes
+    }
+
+    /// Copies the string's UTF-8 bytes into a fixed-size array of `N` bytes,
+    /// zero-padding any bytes past the end of the string.
+    ///
+    /// # Failure
+    ///
+    /// Returns `NotEnoughSpaceError` if the string is longer than `N` bytes.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use inlinable_string::InlineString;
+    ///
+    /// let s = InlineString::from("abc");
+    /// assert_eq!(s.to_fixed_bytes::<5>(), Ok(*b"abc\0\0"));
+    /// assert_eq!(s.to_fixed_bytes::<3>(), Ok(*b"abc"));
+    /// assert!(s.to_fixed_bytes::<2>().is_err());
+    /// ```
+    #[inline]
+    pub fn to_fixed_bytes<const N: usize>(&self) -> Result<[u8; N], NotEnoughSpaceError> {
+        self.assert_sanity();
+        let len = self.len();
+        if len > N {
+            return Err(NotEnoughSpaceError::new(len, N));
+        }
+        let mut bytes = [0; N];
+        bytes[..len].copy_from_slice(self.as_bytes());
+        Ok(bytes)
+    }
+
     /// Pushes the given string onto this string buffer.
     ///
     /// # Examples
@@ -342,23 +838,106 @@ impl InlineString {
         let string_len = string.len();
         let new_length = self.len() + string_len;
 
-        if new_length > INLINE_STRING_CAPACITY {
-            return Err(NotEnoughSpaceError);
+        if new_length > InlineString::CAPACITY {
+            return Err(NotEnoughSpaceError::new(new_length, InlineString::CAPACITY));
         }
 
         unsafe {
             ptr::copy_nonoverlapping(
                 string.as_ptr(),
-                self.bytes.as_mut_ptr().offset(self.length as isize),
+                self.bytes.as_mut_ptr().add(self.len()),
                 string_len,
             );
         }
-        self.length = new_length as u8;
+        self.set_length(new_length);
 
         self.assert_sanity();
         Ok(())
     }
 
+    /// Pushes as much of the given string onto this string buffer as fits,
+    /// stopping at a character boundary, and returns the part that did not
+    /// fit.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use inlinable_string::InlineString;
+    ///
+    /// let mut record = InlineString::from("x".repeat(InlineString::CAPACITY - 4).as_str());
+    /// let rest = record.push_str_partial("añb€c");
+    /// assert!(record.ends_with("xañb"));
+    /// assert_eq!(rest, "€c");
+    ///
+    /// let mut next = InlineString::new();
+    /// assert_eq!(next.push_str_partial(rest), "");
+    /// assert_eq!(next, "€c");
+    /// ```
+    pub fn push_str_partial<'s>(&mut self, string: &'s str) -> &'s str {
+        let mut fits = string.len().min(self.remaining_capacity());
+        while !string.is_char_boundary(fits) {
+            fits -= 1;
+        }
+        let (head, rest) = string.split_at(fits);
+        self.push_str(head)
+            .expect("inlinable_string: internal error: prefix should fit");
+        rest
+    }
+
+    /// Pushes the given string onto this string buffer, silently cutting it
+    /// at the last character boundary that fits.
+    ///
+    /// Use `push_str` to get an error instead, or `push_str_partial` to get
+    /// the part that was cut off.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use inlinable_string::InlineString;
+    ///
+    /// let mut label = InlineString::from("[tag] ");
+    /// label.push_str_truncating(&"é".repeat(InlineString::CAPACITY));
+    /// assert_eq!(label.len(), InlineString::CAPACITY);
+    /// assert!(label.ends_with('é'));
+    /// ```
+    #[inline]
+    pub fn push_str_truncating(&mut self, string: &str) {
+        self.push_str_partial(string);
+    }
+
+    /// Appends each character or string slice from an iterator onto this
+    /// string, failing if they do not all fit.
+    ///
+    /// # Errors
+    ///
+    /// Stops at the first item that does not fit. The items before it stay
+    /// appended, and items after it are not consumed.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use inlinable_string::InlineString;
+    ///
+    /// let mut s = InlineString::from("key=");
+    /// s.try_extend(["v", "a", "l"].iter().copied()).unwrap();
+    /// s.try_extend("ue".chars()).unwrap();
+    /// assert_eq!(s, "key=value");
+    ///
+    /// let error = s.try_extend(std::iter::repeat('!').take(100)).unwrap_err();
+    /// assert_eq!(s.len(), InlineString::CAPACITY);
+    /// assert_eq!(error.needed(), InlineString::CAPACITY + 1);
+    /// ```
+    pub fn try_extend<I>(&mut self, iter: I) -> Result<(), NotEnoughSpaceError>
+    where
+        I: IntoIterator,
+        I::Item: InlinePushable,
+    {
+        for item in iter {
+            item.try_push_onto(self)?;
+        }
+        Ok(())
+    }
+
     /// Adds the given character to the end of the string.
     ///
     /// # Examples
@@ -376,39 +955,134 @@ impl InlineString {
     pub fn push(&mut self, ch: char) -> Result<(), NotEnoughSpaceError> {
         self.assert_sanity();
 
-        let char_len = ch.len_utf8();
-        let new_length = self.len() + char_len;
+        let len = self.len();
+        let new_length = len + ch.len_utf8();
 
-        if new_length > INLINE_STRING_CAPACITY {
-            return Err(NotEnoughSpaceError);
+        if new_length > InlineString::CAPACITY {
+            return Err(NotEnoughSpaceError::new(new_length, InlineString::CAPACITY));
         }
 
-        {
-            let mut slice = &mut self.bytes[self.length as usize..INLINE_STRING_CAPACITY];
-            ch.encode_utf8(&mut slice);
-        }
-        self.length = new_length as u8;
+        ch.encode_utf8(&mut self.bytes[len..InlineString::CAPACITY]);
+        self.set_length(new_length);
 
         self.assert_sanity();
         Ok(())
     }
 
-    /// Works with the underlying buffer as a byte slice.
+    /// Extracts a string slice containing the entire string.
     ///
     /// # Examples
     ///
     /// ```
     /// use inlinable_string::InlineString;
     ///
-    /// let s = InlineString::from("hello");
-    /// assert_eq!(s.as_bytes(), [104, 101, 108, 108, 111]);
+    /// let s = InlineString::from("foo");
+    /// assert_eq!(s.as_str(), "foo");
     /// ```
     #[inline]
-    pub fn as_bytes(&self) -> &[u8] {
+    pub fn as_str(&self) -> &str {
+        self
+    }
+
+    /// Extracts a mutable string slice containing the entire string.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use inlinable_string::InlineString;
+    ///
+    /// let mut s = InlineString::from("foo");
+    /// s.as_mut_str().make_ascii_uppercase();
+    /// assert_eq!(s, "FOO");
+    /// ```
+    #[inline]
+    pub fn as_mut_str(&mut self) -> &mut str {
+        self
+    }
+
+    /// Works with the underlying buffer as a byte slice.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use inlinable_string::InlineString;
+    ///
+    /// let s = InlineString::from("hello");
+    /// assert_eq!(s.as_bytes(), [104, 101, 108, 108, 111]);
+    /// ```
+    #[inline]
+    pub fn as_bytes(&self) -> &[u8] {
         self.assert_sanity();
         &self.bytes[0..self.len()]
     }
 
+    /// Returns a raw pointer to the start of the string's bytes.
+    ///
+    /// The pointer is valid for reads of `self.len()` bytes for as long as
+    /// the string is neither moved nor modified.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use inlinable_string::InlineString;
+    ///
+    /// let s = InlineString::from("hello");
+    /// let first = unsafe { *s.as_ptr() };
+    /// assert_eq!(first, b'h');
+    /// ```
+    #[inline]
+    pub fn as_ptr(&self) -> *const u8 {
+        self.assert_sanity();
+        self.bytes.as_ptr()
+    }
+
+    /// Returns a raw mutable pointer to the start of the string's bytes.
+    ///
+    /// The pointer is valid for reads and writes of `self.len()` bytes for as
+    /// long as the string is neither moved nor otherwise accessed. The caller
+    /// must ensure that the bytes remain valid UTF-8.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use inlinable_string::InlineString;
+    ///
+    /// let mut s = InlineString::from("hello");
+    /// unsafe { *s.as_mut_ptr() = b'j' };
+    /// assert_eq!(s, "jello");
+    /// ```
+    #[inline]
+    pub fn as_mut_ptr(&mut self) -> *mut u8 {
+        self.assert_sanity();
+        self.bytes.as_mut_ptr()
+    }
+
+    /// Returns a guard for writing into the string's spare capacity, to be
+    /// appended with `SpareCapacity::commit`.
+    ///
+    /// The bytes past the string's length must stay zeroed, so the guard
+    /// hands out a scratch buffer and `commit` copies the written bytes into
+    /// the string.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use inlinable_string::InlineString;
+    ///
+    /// let mut s = InlineString::from("abc");
+    /// let mut spare = s.spare_capacity_mut();
+    /// assert_eq!(spare.len(), InlineString::CAPACITY - 3);
+    /// spare[0].write(b'd');
+    /// // Safe because the first byte was just written.
+    /// unsafe { spare.commit(1).unwrap() };
+    /// assert_eq!(s, "abcd");
+    /// ```
+    #[inline]
+    pub fn spare_capacity_mut(&mut self) -> SpareCapacity<'_> {
+        self.assert_sanity();
+        SpareCapacity::inline(self)
+    }
+
     /// Shortens a string to the specified length.
     ///
     /// # Panics
@@ -436,7 +1110,27 @@ impl InlineString {
         );
         assert!(new_len <= self.len());
 
-        self.length = new_len as u8;
+        self.set_length(new_len);
+        self.assert_sanity();
+    }
+
+    /// Shortens this string to its first `n` characters. Does nothing if the
+    /// string has `n` or fewer characters.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use inlinable_string::InlineString;
+    ///
+    /// let mut s = InlineString::from("añb€c");
+    /// s.truncate_chars(2);
+    /// assert_eq!(s, "añ");
+    /// ```
+    #[inline]
+    pub fn truncate_chars(&mut self, n: usize) {
+        if let Some((idx, _)) = self.char_indices().nth(n) {
+            self.set_length(idx);
+        }
         self.assert_sanity();
     }
 
@@ -458,10 +1152,10 @@ impl InlineString {
     pub fn pop(&mut self) -> Option<char> {
         self.assert_sanity();
 
-        match self.char_indices().rev().next() {
+        match self.char_indices().next_back() {
             None => None,
             Some((idx, ch)) => {
-                self.length = idx as u8;
+                self.set_length(idx);
                 self.assert_sanity();
                 Some(ch)
             }
@@ -512,20 +1206,56 @@ impl InlineString {
                 self.len() - next,
             );
         }
-        self.length -= char_len as u8;
+        self.set_length(self.len() - char_len);
 
         self.assert_sanity();
         ch
     }
 
+    /// Removes the characters in the byte range `range` from the string and
+    /// returns them as an iterator. The range is removed even if the iterator
+    /// is dropped before it is fully consumed.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the start or end of the range does not lie on a character
+    /// boundary, or if either is out of bounds.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use inlinable_string::InlineString;
+    ///
+    /// let mut s = InlineString::from("α is alpha");
+    /// let removed: String = s.drain(..5).collect();
+    /// assert_eq!(removed, "α is");
+    /// assert_eq!(s, " alpha");
+    /// ```
+    pub fn drain<R: ops::RangeBounds<usize>>(&mut self, range: R) -> Drain<'_> {
+        let range = drain::resolve_range(self, range);
+        Drain::owned(InlinableString::Inline(self.remove_range(range)))
+    }
+
+    /// Removes the bytes in `range`, which must lie on character boundaries,
+    /// and returns them as a new string.
+    pub(crate) fn remove_range(&mut self, range: ops::Range<usize>) -> InlineString {
+        self.assert_sanity();
+        let removed = InlineString::from(&self[range.clone()]);
+        let len = self.len();
+        self.bytes.copy_within(range.end..len, range.start);
+        self.set_length(len - range.len());
+        self.assert_sanity();
+        removed
+    }
+
     /// Inserts the given bytes at the given position of the string.
     unsafe fn insert_bytes(&mut self, idx: usize, bytes: &[u8]) -> Result<(), NotEnoughSpaceError> {
         let len = self.len();
         let amt = bytes.len();
 
-        // This subtraction does not overflow because `INLINE_STRING_CAPACITY >= self.len()` holds.
-        if amt > INLINE_STRING_CAPACITY - len {
-            return Err(NotEnoughSpaceError);
+        // This subtraction does not overflow because `InlineString::CAPACITY >= self.len()` holds.
+        if amt > InlineString::CAPACITY - len {
+            return Err(NotEnoughSpaceError::new(len + amt, InlineString::CAPACITY));
         }
 
         let ptr = self.bytes.as_mut_ptr().add(idx);
@@ -538,8 +1268,7 @@ impl InlineString {
         );
         // Copy the bytes into the buffer.
         ptr::copy(bytes.as_ptr(), self.bytes.as_mut_ptr().add(idx), amt);
-        // `amt` is less than `u8::MAX` becuase `INLINE_STRING_CAPACITY < u8::MAX` holds.
-        self.length += amt as u8;
+        self.set_length(len + amt);
 
         Ok(())
     }
@@ -600,6 +1329,203 @@ impl InlineString {
         Ok(())
     }
 
+    /// Replaces the byte range `range` with `replace_with`, moving the rest of
+    /// the string only once.
+    ///
+    /// # Errors
+    ///
+    /// Returns `NotEnoughSpaceError`, and leaves the string unchanged, if the
+    /// result would be longer than `InlineString::CAPACITY`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the start or end of the range does not lie on a character
+    /// boundary, or if either is out of bounds.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use inlinable_string::InlineString;
+    ///
+    /// let mut s = InlineString::from("hello world");
+    /// s.try_replace_range(6.., "there").unwrap();
+    /// assert_eq!(s, "hello there");
+    ///
+    /// let too_long = "x".repeat(InlineString::CAPACITY);
+    /// assert!(s.try_replace_range(..5, &too_long).is_err());
+    /// assert_eq!(s, "hello there");
+    /// ```
+    pub fn try_replace_range<R: ops::RangeBounds<usize>>(
+        &mut self,
+        range: R,
+        replace_with: &str,
+    ) -> Result<(), NotEnoughSpaceError> {
+        self.assert_sanity();
+        let range = drain::resolve_range(self, range);
+        let len = self.len();
+        let new_len = len - range.len() + replace_with.len();
+        if new_len > InlineString::CAPACITY {
+            return Err(NotEnoughSpaceError::new(new_len, InlineString::CAPACITY));
+        }
+
+        let tail_start = range.start + replace_with.len();
+        self.bytes.copy_within(range.end..len, tail_start);
+        self.bytes[range.start..tail_start].copy_from_slice(replace_with.as_bytes());
+        self.set_length(new_len);
+
+        self.assert_sanity();
+        Ok(())
+    }
+
+    /// Rotates the string in place so that its first `n` characters move to
+    /// the end. `n` wraps around the number of characters in the string.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use inlinable_string::InlineString;
+    ///
+    /// let mut s = InlineString::from("héllo");
+    /// s.rotate_left_chars(2);
+    /// assert_eq!(s, "llohé");
+    /// s.rotate_left_chars(8);
+    /// assert_eq!(s, "héllo");
+    /// ```
+    #[inline]
+    pub fn rotate_left_chars(&mut self, n: usize) {
+        self.assert_sanity();
+        let mid = rotate_left_mid(self, n);
+        // Rotating around a character boundary keeps the contents valid UTF-8.
+        unsafe {
+            self.as_mut_slice().rotate_left(mid);
+        }
+        self.assert_sanity();
+    }
+
+    /// Rotates the string in place so that its last `n` characters move to
+    /// the front. `n` wraps around the number of characters in the string.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use inlinable_string::InlineString;
+    ///
+    /// let mut s = InlineString::from("héllo");
+    /// s.rotate_right_chars(4);
+    /// assert_eq!(s, "élloh");
+    /// ```
+    #[inline]
+    pub fn rotate_right_chars(&mut self, n: usize) {
+        self.assert_sanity();
+        let mid = rotate_right_mid(self, n);
+        unsafe {
+            self.as_mut_slice().rotate_left(mid);
+        }
+        self.assert_sanity();
+    }
+
+    /// Retains only the characters for which `f` returns true, removing the
+    /// rest in place.
+    ///
+    /// If `f` panics, the string is left holding the characters kept so far.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use inlinable_string::InlineString;
+    ///
+    /// let mut s = InlineString::from("h-é-l-l-o");
+    /// s.retain(|c| c != '-');
+    /// assert_eq!(s, "héllo");
+    /// ```
+    #[inline]
+    pub fn retain<F: FnMut(char) -> bool>(&mut self, mut f: F) {
+        self.assert_sanity();
+        let len = self.len();
+        let mut read = 0;
+        let mut kept = KeepOnDrop {
+            string: self,
+            write: 0,
+        };
+
+        // Bytes from `read` onwards are untouched, so they are still valid
+        // UTF-8 and start on a character boundary.
+        while let Some(ch) =
+            unsafe { str::from_utf8_unchecked(&kept.string.bytes[read..len]) }.chars().next()
+        {
+            let ch_len = ch.len_utf8();
+            if f(ch) {
+                let write = kept.write;
+                kept.string.bytes.copy_within(read..read + ch_len, write);
+                kept.write += ch_len;
+            }
+            read += ch_len;
+        }
+
+        drop(kept);
+        self.assert_sanity();
+    }
+
+    /// Retains only the ASCII bytes for which `predicate` returns true,
+    /// removing the rest in place. Non-ASCII characters are always kept.
+    ///
+    /// This is faster than `retain` for byte-oriented filters such as
+    /// stripping punctuation or control characters: it never decodes UTF-8,
+    /// and it classifies the string eight bytes at a time, copying runs of
+    /// non-ASCII bytes without consulting the predicate. It does not use
+    /// `std::simd`, which is not available on stable Rust.
+    ///
+    /// If `predicate` panics, the string is left holding the bytes kept so
+    /// far.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use inlinable_string::InlineString;
+    ///
+    /// let mut s = InlineString::from("a1b2c3é4");
+    /// s.retain_bytes(|b: u8| !b.is_ascii_digit());
+    /// assert_eq!(s, "abcé");
+    /// ```
+    #[inline]
+    pub fn retain_bytes<P: AsciiPredicate>(&mut self, mut predicate: P) {
+        self.assert_sanity();
+        let len = self.len();
+        let mut kept = KeepOnDrop {
+            string: self,
+            write: 0,
+        };
+
+        let KeepOnDrop {
+            ref mut string,
+            ref mut write,
+        } = kept;
+        retain_ascii_in(&mut string.bytes[..len], write, &mut predicate);
+
+        drop(kept);
+        self.assert_sanity();
+    }
+
+    /// Removes every non-overlapping occurrence of `pat` from the string in
+    /// place.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use inlinable_string::InlineString;
+    ///
+    /// let mut s = InlineString::from("a, b, c");
+    /// s.remove_matches(", ");
+    /// assert_eq!(s, "abc");
+    /// ```
+    pub fn remove_matches(&mut self, pat: &str) {
+        self.assert_sanity();
+        let len = self.len();
+        let new_len = unsafe { remove_matches_in(&mut self.bytes[..len], pat) };
+        self.set_length(new_len);
+        self.assert_sanity();
+    }
+
     /// Views the internal string buffer as a mutable sequence of bytes.
     ///
     /// # Safety
@@ -660,6 +1586,57 @@ impl InlineString {
         self.length == 0
     }
 
+    /// Returns how many more bytes can be pushed onto this string before it
+    /// runs out of space.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use inlinable_string::InlineString;
+    ///
+    /// let mut s = InlineString::from("foo");
+    /// let suffix = "bar";
+    /// if suffix.len() <= s.remaining_capacity() {
+    ///     s.push_str(suffix).unwrap();
+    /// }
+    /// assert_eq!(s.remaining_capacity(), InlineString::CAPACITY - 6);
+    /// ```
+    #[inline]
+    pub fn remaining_capacity(&self) -> usize {
+        InlineString::CAPACITY - self.len()
+    }
+
+    /// Returns true if this string's contents are equal to `other`.
+    ///
+    /// Unlike `==`, this is a `const fn`, so it can be used in constant
+    /// evaluation, for example to check a compile-time table against string
+    /// literals.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use inlinable_string::InlineString;
+    ///
+    /// let s = InlineString::from("foo");
+    /// assert!(s.eq_str("foo"));
+    /// assert!(!s.eq_str("food"));
+    /// ```
+    #[inline]
+    pub const fn eq_str(&self, other: &str) -> bool {
+        let other = other.as_bytes();
+        if self.length as usize != other.len() {
+            return false;
+        }
+        let mut i = 0;
+        while i < other.len() {
+            if self.bytes[i] != other[i] {
+                return false;
+            }
+            i += 1;
+        }
+        true
+    }
+
     /// Truncates the string, returning it to 0 length.
     ///
     /// # Examples
@@ -674,15 +1651,132 @@ impl InlineString {
     #[inline]
     pub fn clear(&mut self) {
         self.assert_sanity();
-        self.length = 0;
+        self.set_length(0);
         self.assert_sanity();
     }
 }
 
+/// Returns the byte index to rotate `string` left around so that its first
+/// `n` characters (modulo its character count) move to the end.
+pub(crate) fn rotate_left_mid(string: &str, n: usize) -> usize {
+    let count = string.chars().count();
+    if count == 0 {
+        return 0;
+    }
+    match string.char_indices().nth(n % count) {
+        Some((idx, _)) => idx,
+        None => string.len(),
+    }
+}
+
+/// Returns the byte index to rotate `string` left around so that its last
+/// `n` characters (modulo its character count) move to the front.
+pub(crate) fn rotate_right_mid(string: &str, n: usize) -> usize {
+    let count = string.chars().count();
+    if count == 0 {
+        return 0;
+    }
+    rotate_left_mid(string, count - n % count)
+}
+
+/// Truncates a string being filtered in place to the bytes kept so far when
+/// dropped, including when a caller-supplied filter panics, so that it never
+/// holds the partially shifted bytes past them.
+struct KeepOnDrop<'a> {
+    string: &'a mut InlineString,
+    write: usize,
+}
+
+impl<'a> Drop for KeepOnDrop<'a> {
+    fn drop(&mut self) {
+        self.string.set_length(self.write);
+    }
+}
+
+/// Moves the bytes of `bytes` that are either non-ASCII or kept by
+/// `predicate` down to the front, counting them in `*write`, which must start
+/// at zero.
+///
+/// `*write` is kept up to date as bytes are moved. The predicate is only
+/// called on ASCII bytes, which always start a character, so if it panics
+/// `bytes[..*write]` holds whole characters and stays valid UTF-8 as long as
+/// `bytes` was.
+pub(crate) fn retain_ascii_in<P: AsciiPredicate>(
+    bytes: &mut [u8],
+    write: &mut usize,
+    predicate: &mut P,
+) {
+    const HIGH_BITS: u64 = 0x8080_8080_8080_8080;
+
+    let len = bytes.len();
+    let mut read = 0;
+    while read + 8 <= len {
+        let mut word = [0; 8];
+        word.copy_from_slice(&bytes[read..read + 8]);
+        let high = u64::from_ne_bytes(word) & HIGH_BITS;
+        if high == HIGH_BITS {
+            // Eight non-ASCII bytes are always kept.
+            bytes.copy_within(read..read + 8, *write);
+            *write += 8;
+        } else if high == 0 {
+            for &byte in &word {
+                if predicate.keep(byte) {
+                    bytes[*write] = byte;
+                    *write += 1;
+                }
+            }
+        } else {
+            for &byte in &word {
+                if !byte.is_ascii() || predicate.keep(byte) {
+                    bytes[*write] = byte;
+                    *write += 1;
+                }
+            }
+        }
+        read += 8;
+    }
+
+    for read in read..len {
+        let byte = bytes[read];
+        if !byte.is_ascii() || predicate.keep(byte) {
+            bytes[*write] = byte;
+            *write += 1;
+        }
+    }
+}
+
+/// Removes every non-overlapping occurrence of `pat` from `bytes` by moving
+/// the remaining bytes down, and returns their new length. Bytes past the new
+/// length are left as they were.
+///
+/// # Safety
+///
+/// `bytes` must be valid UTF-8.
+pub(crate) unsafe fn remove_matches_in(bytes: &mut [u8], pat: &str) -> usize {
+    if pat.is_empty() {
+        return bytes.len();
+    }
+    let len = bytes.len();
+    let mut read = 0;
+    let mut write = 0;
+    loop {
+        // Everything from `read` on is untouched, and starts on a character
+        // boundary.
+        let found = str::from_utf8_unchecked(&bytes[read..]).find(pat);
+        let end = found.map_or(len, |idx| read + idx);
+        bytes.copy_within(read..end, write);
+        write += end - read;
+        match found {
+            Some(_) => read = end + pat.len(),
+            None => return write,
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
-    use alloc::string::String;
-    use super::{InlineString, NotEnoughSpaceError, INLINE_STRING_CAPACITY};
+    use alloc::string::{String, ToString};
+    use super::{InlineString, NotEnoughSpaceError};
 
     #[test]
     fn test_push_str() {
@@ -691,8 +1785,11 @@ mod tests {
         assert_eq!(s, "small");
 
         let long_str = "this is a really long string that is much larger than
-                        INLINE_STRING_CAPACITY and so cannot be stored inline.";
-        assert_eq!(s.push_str(long_str), Err(NotEnoughSpaceError));
+                        InlineString::CAPACITY and so cannot be stored inline.";
+        assert_eq!(
+            s.push_str(long_str),
+            Err(NotEnoughSpaceError::new(5 + long_str.len(), InlineString::CAPACITY))
+        );
         assert_eq!(s, "small");
     }
 
@@ -700,22 +1797,28 @@ mod tests {
     fn test_push() {
         let mut s = InlineString::new();
 
-        for _ in 0..INLINE_STRING_CAPACITY {
+        for _ in 0..InlineString::CAPACITY {
             assert!(s.push('a').is_ok());
         }
 
-        assert_eq!(s.push('a'), Err(NotEnoughSpaceError));
+        assert_eq!(
+            s.push('a'),
+            Err(NotEnoughSpaceError::new(InlineString::CAPACITY + 1, InlineString::CAPACITY))
+        );
     }
 
     #[test]
     fn test_insert() {
         let mut s = InlineString::new();
 
-        for _ in 0..INLINE_STRING_CAPACITY {
+        for _ in 0..InlineString::CAPACITY {
             assert!(s.insert(0, 'a').is_ok());
         }
 
-        assert_eq!(s.insert(0, 'a'), Err(NotEnoughSpaceError));
+        assert_eq!(
+            s.insert(0, 'a'),
+            Err(NotEnoughSpaceError::new(InlineString::CAPACITY + 1, InlineString::CAPACITY))
+        );
     }
 
     #[test]
@@ -725,7 +1828,7 @@ mod tests {
         let mut s = InlineString::new();
         let mut normal_string = String::new();
 
-        for _ in 0..INLINE_STRING_CAPACITY {
+        for _ in 0..InlineString::CAPACITY {
             assert!(write!(&mut s, "a").is_ok());
             assert!(write!(&mut normal_string, "a").is_ok());
         }
@@ -733,13 +1836,461 @@ mod tests {
         assert_eq!(write!(&mut s, "a"), Err(Error));
         assert_eq!(&normal_string[..], &s[..]);
     }
+
+    #[test]
+    fn test_as_ptr() {
+        let mut s = InlineString::from("abc");
+        assert_eq!(s.as_ptr(), s.as_bytes().as_ptr());
+        unsafe {
+            let ptr = s.as_mut_ptr();
+            *ptr.add(2) = b'z';
+        }
+        assert_eq!(s, "abz");
+    }
+
+    #[test]
+    fn test_to_fixed_bytes() {
+        use core::convert::TryInto;
+
+        let s = InlineString::from("héllo");
+        let exact: [u8; 6] = (&s).try_into().unwrap();
+        assert_eq!(&exact, "héllo".as_bytes());
+        let padded: [u8; 8] = s.clone().try_into().unwrap();
+        assert_eq!(&padded, b"h\xc3\xa9llo\0\0");
+        let short: Result<[u8; 5], _> = s.try_into();
+        assert_eq!(short, Err(NotEnoughSpaceError::new(6, 5)));
+    }
+
+    #[test]
+    fn test_zeroed_tail() {
+        let mut s = InlineString::from("héllo wörld");
+        s.truncate(8);
+        s.pop();
+        s.remove(0);
+        s.insert_str(2, "xyz").unwrap();
+        assert_eq!(s, "éxyzllo ");
+        let len = s.len();
+        assert!(s.into_bytes()[len..].iter().all(|&b| b == 0));
+    }
+
+    #[test]
+    fn test_retain() {
+        let mut s = InlineString::from("a€b€c");
+        s.retain(|c| c != '€');
+        assert_eq!(s, "abc");
+        s.retain(|_| false);
+        assert!(s.is_empty());
+
+        let mut s = InlineString::from("x-€-y\u{7f}");
+        s.retain_bytes(|b: u8| b != b'-' && !b.is_ascii_control());
+        assert_eq!(s, "x€y");
+        let len = s.len();
+        assert!(s.into_bytes()[len..].iter().all(|&b| b == 0));
+
+        // Whole words of ASCII, of non-ASCII, and of both, plus a tail.
+        for &input in &["a-b-c-d-€€€€é-x-ü-y-", "--------€€€€€€--", "ab-€"] {
+            let mut bytes = InlineString::from(input);
+            bytes.retain_bytes(|b: u8| b != b'-');
+            let mut chars = InlineString::from(input);
+            chars.retain(|c| c != '-');
+            assert_eq!(bytes, chars);
+        }
+    }
+
+    #[cfg(not(feature = "no_std"))]
+    #[test]
+    fn test_retain_panic() {
+        let mut s = InlineString::from("ab€cd");
+        let result = std::panic::catch_unwind(core::panic::AssertUnwindSafe(|| {
+            s.retain_bytes(|b: u8| if b == b'c' { panic!() } else { b != b'a' });
+        }));
+        assert!(result.is_err());
+        assert_eq!(s, "b€");
+    }
+
+    #[cfg(not(feature = "no_std"))]
+    #[test]
+    fn test_retain_chars_panic() {
+        let mut s = InlineString::from("ab€cd");
+        let result = std::panic::catch_unwind(core::panic::AssertUnwindSafe(|| {
+            s.retain(|c| if c == 'c' { panic!() } else { c != 'a' });
+        }));
+        assert!(result.is_err());
+        assert_eq!(s, "b€");
+        assert_eq!(s.clone().into_bytes()[4..], [0; InlineString::CAPACITY - 4]);
+    }
+
+    #[test]
+    fn test_remaining_capacity() {
+        let mut s = InlineString::new();
+        assert_eq!(s.remaining_capacity(), InlineString::CAPACITY);
+        s.push('€').unwrap();
+        assert_eq!(s.remaining_capacity(), InlineString::CAPACITY - 3);
+        while s.push('a').is_ok() {}
+        assert_eq!(s.remaining_capacity(), 0);
+    }
+
+    #[test]
+    fn test_truncate_chars() {
+        let mut s = InlineString::from("añb€c");
+        s.truncate_chars(5);
+        assert_eq!(s, "añb€c");
+        s.truncate_chars(4);
+        assert_eq!(s, "añb€");
+        s.truncate_chars(1);
+        assert_eq!(s, "a");
+        assert_eq!(s.clone().into_bytes()[1..], [0; InlineString::CAPACITY - 1]);
+    }
+
+    #[test]
+    fn test_push_str_partial() {
+        let mut s = InlineString::new();
+        let long = "€".repeat(InlineString::CAPACITY);
+        let rest = s.push_str_partial(&long);
+        assert_eq!(s.len(), InlineString::CAPACITY / 3 * 3);
+        assert_eq!(s.chars().count() + rest.chars().count(), InlineString::CAPACITY);
+        assert_eq!(s.push_str_partial("a"), "a");
+        assert_eq!(s.push_str_partial(""), "");
+    }
+
+    #[test]
+    fn test_push_str_truncating() {
+        let mut s = InlineString::from("ab");
+        s.push_str_truncating("cd");
+        assert_eq!(s, "abcd");
+
+        let long = "€".repeat(InlineString::CAPACITY);
+        s.push_str_truncating(&long);
+        assert_eq!(&s[..4], "abcd");
+        assert_eq!(s.len(), 4 + (InlineString::CAPACITY - 4) / 3 * 3);
+        assert!(s[4..].chars().all(|c| c == '€'));
+    }
+
+    #[test]
+    fn test_not_enough_space_error() {
+        let mut s = InlineString::from("abc");
+        let error = s.push_str(&"x".repeat(InlineString::CAPACITY)).unwrap_err();
+        assert_eq!(error.needed(), InlineString::CAPACITY + 3);
+        assert_eq!(error.capacity(), InlineString::CAPACITY);
+
+        let error = NotEnoughSpaceError::new(41, 30);
+        assert_eq!(error.to_string(), "needed 41 bytes, but the capacity is 30");
+    }
+
+    #[test]
+    fn test_display_flags() {
+        use alloc::format;
+
+        let s = InlineString::from("añb");
+        assert_eq!(format!("[{:>5}]", s), "[  añb]");
+        assert_eq!(format!("[{:*<5}]", s), "[añb**]");
+        assert_eq!(format!("[{:.2}]", s), "[añ]");
+        assert_eq!(format!("[{:^7.2}]", s), "[  añ   ]");
+    }
+
+    #[test]
+    fn test_from_static() {
+        const GREETING: InlineString = InlineString::from_static("héllo");
+        assert_eq!(GREETING, "héllo");
+        assert_eq!(GREETING, InlineString::from("héllo"));
+        assert!(InlineString::from_static("").is_empty());
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_from_static_too_long() {
+        let long: &'static str = "this literal is far too long to be stored inline";
+        InlineString::from_static(long);
+    }
+
+    #[test]
+    fn test_try_from_iter() {
+        let chars = ['a', 'ñ', '€'];
+        assert_eq!(InlineString::try_from_iter(chars.iter()).unwrap(), "añ€");
+        assert_eq!(InlineString::try_from_iter(chars.iter().copied()).unwrap(), "añ€");
+        assert_eq!(InlineString::try_from_iter(vec!["ab", "", "c"]).unwrap(), "abc");
+        assert!(InlineString::try_from_iter(core::iter::empty::<char>())
+            .unwrap()
+            .is_empty());
+
+        let mut iter = core::iter::repeat('€').take(InlineString::CAPACITY);
+        let (partial, error) = InlineString::try_from_iter(iter.by_ref()).unwrap_err();
+        assert_eq!(partial.len(), InlineString::CAPACITY / 3 * 3);
+        assert_eq!(error.needed(), partial.len() + 3);
+        // The character that did not fit was consumed; the rest were not.
+        assert_eq!(
+            iter.count(),
+            InlineString::CAPACITY - InlineString::CAPACITY / 3 - 1
+        );
+    }
+
+    #[test]
+    fn test_try_extend() {
+        let mut s = InlineString::from("a");
+        s.try_extend(['ñ', '€'].iter()).unwrap();
+        s.try_extend(vec!["b", "c"]).unwrap();
+        assert_eq!(s, "añ€bc");
+
+        let fill = "x".repeat(InlineString::CAPACITY - s.len() - 1);
+        let mut iter = vec![fill.as_str(), "yz", "w"].into_iter();
+        let error = s.try_extend(iter.by_ref()).unwrap_err();
+        assert_eq!(
+            error,
+            NotEnoughSpaceError::new(InlineString::CAPACITY + 1, InlineString::CAPACITY)
+        );
+        assert_eq!(s.len(), InlineString::CAPACITY - 1);
+        assert_eq!(iter.next(), Some("w"));
+    }
+
+    #[test]
+    fn test_as_str() {
+        let mut s = InlineString::from("añb");
+        assert_eq!(s.as_str(), "añb");
+        s.as_mut_str().make_ascii_uppercase();
+        assert_eq!(s.as_str(), "AñB");
+    }
+
+    #[test]
+    fn test_into_array() {
+        let mut s = InlineString::from("añb€");
+        s.pop();
+        assert_eq!(&s.as_array()[..s.len()], "añb".as_bytes());
+        assert_eq!(s.as_array()[s.len()..], [0; InlineString::CAPACITY - 4]);
+
+        let array = *s.as_array();
+        let (bytes, len) = s.into_array();
+        assert_eq!(bytes, array);
+        assert_eq!(len, 4);
+    }
+
+    #[test]
+    fn test_eq() {
+        let mut a = InlineString::from("hello world");
+        a.truncate(5);
+        let b = InlineString::from("hello");
+        assert_eq!(a, b);
+        assert_ne!(a, InlineString::from("hellp"));
+        assert_ne!(a, InlineString::from("hell"));
+        assert_eq!(InlineString::new(), InlineString::from(""));
+    }
+
+    #[test]
+    fn test_try_from_string() {
+        use core::convert::TryFrom;
+
+        let owned = String::from("owned");
+        assert_eq!(InlineString::try_from(&owned).unwrap(), "owned");
+        assert_eq!(InlineString::try_from(owned).unwrap(), "owned");
+
+        let long = "a".repeat(InlineString::CAPACITY + 1);
+        let error = NotEnoughSpaceError::new(InlineString::CAPACITY + 1, InlineString::CAPACITY);
+        assert_eq!(InlineString::try_from(&long), Err(error));
+        assert_eq!(InlineString::try_from(long), Err(error));
+    }
+
+    #[test]
+    fn test_try_from_char() {
+        use core::convert::TryFrom;
+
+        assert_eq!(InlineString::try_from('a').unwrap(), "a");
+        let crab = InlineString::try_from('🦀').unwrap();
+        assert_eq!(crab, "🦀");
+        assert_eq!(crab.len(), 4);
+    }
+
+    #[test]
+    fn test_try_from_bytes() {
+        use crate::Error;
+        use core::convert::TryFrom;
+
+        assert_eq!(InlineString::try_from("é".as_bytes()).unwrap(), "é");
+        assert!(matches!(
+            InlineString::try_from(&"é".as_bytes()[..1]),
+            Err(Error::Utf8(_))
+        ));
+        let long = [b'a'; InlineString::CAPACITY + 1];
+        assert!(matches!(
+            InlineString::try_from(&long[..]),
+            Err(Error::NotEnoughSpace(_))
+        ));
+    }
+
+    #[test]
+    fn test_from_utf8_array() {
+        use crate::Error;
+
+        let mut bytes = [0xff; InlineString::CAPACITY];
+        bytes[..2].copy_from_slice("é".as_bytes());
+        let s = InlineString::from_utf8_array(bytes, 2).unwrap();
+        assert_eq!(s, "é");
+        assert_eq!(s.into_bytes()[2..], [0; InlineString::CAPACITY - 2]);
+
+        assert!(matches!(
+            InlineString::from_utf8_array(bytes, 1),
+            Err(Error::Utf8(_))
+        ));
+        assert!(matches!(
+            InlineString::from_utf8_array(bytes, InlineString::CAPACITY + 1),
+            Err(Error::NotEnoughSpace(_))
+        ));
+        assert_eq!(
+            InlineString::from_utf8_array([0; InlineString::CAPACITY], 0).unwrap(),
+            ""
+        );
+    }
+
+    #[test]
+    fn test_try_replace_range() {
+        let mut s = InlineString::from("añb€c");
+        s.try_replace_range(1..3, "NN").unwrap();
+        assert_eq!(s, "aNNb€c");
+        s.try_replace_range(4..7, "").unwrap();
+        assert_eq!(s, "aNNbc");
+        s.try_replace_range(..=0, "ééé").unwrap();
+        assert_eq!(s, "éééNNbc");
+
+        let fill = "x".repeat(InlineString::CAPACITY - s.len() + 2);
+        s.try_replace_range(0..2, &fill).unwrap();
+        assert_eq!(s.len(), InlineString::CAPACITY);
+        assert_eq!(
+            s.try_replace_range(0..0, "y"),
+            Err(NotEnoughSpaceError::new(InlineString::CAPACITY + 1, InlineString::CAPACITY))
+        );
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_try_replace_range_not_char_boundary() {
+        let mut s = InlineString::from("é");
+        let _ = s.try_replace_range(..1, "e");
+    }
+
+    #[test]
+    fn test_borrow() {
+        use alloc::borrow::BorrowMut;
+        use alloc::collections::BTreeSet;
+
+        let mut set = BTreeSet::new();
+        set.insert(InlineString::from("key"));
+        assert!(set.contains("key"));
+        assert!(!set.contains("other"));
+
+        let mut s = InlineString::from("abc");
+        BorrowMut::<str>::borrow_mut(&mut s).make_ascii_uppercase();
+        assert_eq!(s, "ABC");
+    }
+
+    #[cfg(not(feature = "no_std"))]
+    #[test]
+    fn test_hash_map_lookup() {
+        use std::collections::HashMap;
+
+        let mut map = HashMap::new();
+        map.insert(InlineString::from("key"), 1);
+        assert_eq!(map.get("key"), Some(&1));
+    }
+
+    #[test]
+    fn test_ord() {
+        use alloc::collections::BTreeMap;
+
+        let mut keys = [
+            InlineString::from("pear"),
+            InlineString::from("apple"),
+            InlineString::from("fig"),
+        ];
+        keys.sort();
+        assert_eq!(keys, ["apple", "fig", "pear"]);
+
+        let mut map = BTreeMap::new();
+        map.insert(InlineString::from("b"), 2);
+        map.insert(InlineString::from("a"), 1);
+        assert_eq!(map.keys().next().unwrap(), "a");
+
+        let fig = InlineString::from("fig");
+        assert!(fig < *"pear");
+        assert!(fig > "apple");
+        assert!("apple" < fig);
+        assert!(alloc::borrow::Cow::Borrowed("fig") <= fig);
+    }
+
+    #[test]
+    fn test_remove_matches() {
+        let mut s = InlineString::from("ééxééxéé");
+        s.remove_matches("éé");
+        assert_eq!(s, "xx");
+
+        let mut s = InlineString::from("aaaaa");
+        s.remove_matches("aa");
+        assert_eq!(s, "a");
+
+        let mut s = InlineString::from("abc");
+        s.remove_matches("");
+        assert_eq!(s, "abc");
+        s.remove_matches("abc");
+        assert_eq!(s, "");
+    }
+
+    #[test]
+    fn test_eq_str() {
+        let s = InlineString::from("añb");
+        assert!(s.eq_str("añb"));
+        assert!(!s.eq_str("añc"));
+        assert!(!s.eq_str("añ"));
+        assert!(InlineString::new().eq_str(""));
+    }
+
+    #[test]
+    fn test_rotate_chars() {
+        let mut s = InlineString::from("añb€c");
+        s.rotate_left_chars(0);
+        assert_eq!(s, "añb€c");
+        s.rotate_left_chars(1);
+        assert_eq!(s, "ñb€ca");
+        s.rotate_left_chars(3);
+        assert_eq!(s, "cañb€");
+        s.rotate_right_chars(2);
+        assert_eq!(s, "b€cañ");
+        s.rotate_right_chars(5);
+        assert_eq!(s, "b€cañ");
+        s.rotate_right_chars(11);
+        assert_eq!(s, "ñb€ca");
+
+        let mut empty = InlineString::new();
+        empty.rotate_left_chars(3);
+        empty.rotate_right_chars(3);
+        assert!(empty.is_empty());
+    }
 }
 
 #[cfg(test)]
 #[cfg(feature = "nightly")]
 mod benches {
-    use test::Bencher;
+    use super::InlineString;
+    use test::{black_box, Bencher};
+
+    const PUNCTUATED: &str = "a-b, c; d! (e) é-f, g; h! (i)";
 
     #[bench]
     fn its_fast(_b: &mut Bencher) {}
+
+    #[bench]
+    fn bench_inline_string_retain(b: &mut Bencher) {
+        let s = InlineString::from(PUNCTUATED);
+        b.iter(|| {
+            let mut s = black_box(s.clone());
+            s.retain(|c| !c.is_ascii_punctuation());
+            black_box(s);
+        });
+    }
+
+    #[bench]
+    fn bench_inline_string_retain_bytes(b: &mut Bencher) {
+        let s = InlineString::from(PUNCTUATED);
+        b.iter(|| {
+            let mut s = black_box(s.clone());
+            s.retain_bytes(|b: u8| !b.is_ascii_punctuation());
+            black_box(s);
+        });
+    }
 }
@@ -0,0 +1,435 @@
+// Copyright 2015, The inlinable_string crate Developers. See the COPYRIGHT file
+// at the top-level directory of this distribution.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license <LICENSE-MIT
+// or http://opensource.org/licenses/MIT>, at your option. This file may not be
+// copied, modified, or distributed except according to those terms.
+
+//! Adapters between `InlinableString` and the `std::io` traits.
+//!
+//! `InlinableString` already implements `fmt::Write`, so it does not implement
+//! `io::Write` itself: with both traits in scope `write!` would be ambiguous.
+//! Wrap the string in a `Utf8Writer` instead when a byte-oriented sink is
+//! needed, and in a `Reader` to read its bytes back through `Read`, `BufRead`
+//! and `Seek`.
+//!
+//! # Examples
+//!
+//! ```
+//! use inlinable_string::InlinableString;
+//! use inlinable_string::io::Utf8Writer;
+//! use std::io::Write;
+//!
+//! let mut s = InlinableString::new();
+//! let mut writer = Utf8Writer::new(&mut s);
+//! writer.write_all(b"caf\xc3").unwrap();
+//! writer.write_all(b"\xa9").unwrap();
+//! writer.finish().unwrap();
+//! assert_eq!(s, "café");
+//! ```
+
+use crate::{InlinableString, StringExt};
+use std::io::{self, BufRead, Read, Seek, SeekFrom};
+use std::str;
+
+/// An `io::Write` adapter that appends UTF-8 bytes to an `InlinableString`.
+///
+/// Bytes are validated as they arrive. A character split across two writes is
+/// held back until its remaining bytes are written, so the adapter can sit
+/// under `io::BufWriter` or any other writer that chunks its output without
+/// regard for character boundaries. Only a sequence that can never become
+/// valid UTF-8 fails, with an error of kind `InvalidData`.
+///
+/// Call `finish` once all bytes are written to check that no partial
+/// character is still pending.
+#[derive(Debug)]
+pub struct Utf8Writer<'a> {
+    string: &'a mut InlinableString,
+    pending: [u8; 3],
+    pending_len: u8,
+}
+
+impl<'a> Utf8Writer<'a> {
+    /// Creates a writer that appends to `string`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use inlinable_string::InlinableString;
+    /// use inlinable_string::io::Utf8Writer;
+    /// use std::io::Write;
+    ///
+    /// let mut s = InlinableString::from("x = ");
+    /// write!(Utf8Writer::new(&mut s), "{}", 42).unwrap();
+    /// assert_eq!(s, "x = 42");
+    /// ```
+    #[inline]
+    pub fn new(string: &'a mut InlinableString) -> Utf8Writer<'a> {
+        Utf8Writer {
+            string,
+            pending: [0; 3],
+            pending_len: 0,
+        }
+    }
+
+    /// Returns the bytes of a partially written character that are waiting
+    /// for the rest of their sequence.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use inlinable_string::InlinableString;
+    /// use inlinable_string::io::Utf8Writer;
+    /// use std::io::Write;
+    ///
+    /// let mut s = InlinableString::new();
+    /// let mut writer = Utf8Writer::new(&mut s);
+    /// writer.write_all(b"a\xe2\x82").unwrap();
+    /// assert_eq!(writer.pending(), b"\xe2\x82");
+    /// ```
+    #[inline]
+    pub fn pending(&self) -> &[u8] {
+        &self.pending[..self.pending_len as usize]
+    }
+
+    /// Consumes the writer, failing with an error of kind `UnexpectedEof` if
+    /// the input ended in the middle of a character.
+    ///
+    /// The pending bytes are dropped either way; everything before them has
+    /// already been appended to the string.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use inlinable_string::InlinableString;
+    /// use inlinable_string::io::Utf8Writer;
+    /// use std::io::{ErrorKind, Write};
+    ///
+    /// let mut s = InlinableString::new();
+    /// let mut writer = Utf8Writer::new(&mut s);
+    /// writer.write_all(b"ok\xc3").unwrap();
+    /// assert_eq!(writer.finish().unwrap_err().kind(), ErrorKind::UnexpectedEof);
+    /// assert_eq!(s, "ok");
+    /// ```
+    pub fn finish(self) -> io::Result<()> {
+        if self.pending_len == 0 {
+            Ok(())
+        } else {
+            Err(io::Error::new(
+                io::ErrorKind::UnexpectedEof,
+                "incomplete UTF-8 sequence at end of input",
+            ))
+        }
+    }
+
+    /// Feeds the start of `buf` into the pending partial character, returning
+    /// how many bytes of `buf` it took.
+    fn complete_pending(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let pending_len = self.pending_len as usize;
+        let width = utf8_width(self.pending[0]);
+        let take = (width - pending_len).min(buf.len());
+
+        let mut bytes = [0; 4];
+        bytes[..pending_len].copy_from_slice(self.pending());
+        bytes[pending_len..pending_len + take].copy_from_slice(&buf[..take]);
+        let bytes = &bytes[..pending_len + take];
+
+        match str::from_utf8(bytes) {
+            Ok(c) => {
+                self.string.push_str(c);
+                self.pending_len = 0;
+            }
+            Err(e) if e.error_len().is_none() => {
+                self.pending[..bytes.len()].copy_from_slice(bytes);
+                self.pending_len = bytes.len() as u8;
+            }
+            Err(e) => return Err(invalid_data(e)),
+        }
+        Ok(take)
+    }
+}
+
+impl<'a> io::Write for Utf8Writer<'a> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let mut written = 0;
+        if self.pending_len > 0 {
+            written = self.complete_pending(buf)?;
+            if self.pending_len > 0 {
+                return Ok(written);
+            }
+        }
+
+        let rest = &buf[written..];
+        match str::from_utf8(rest) {
+            Ok(s) => {
+                self.string.push_str(s);
+                Ok(buf.len())
+            }
+            Err(e) => {
+                let valid = e.valid_up_to();
+                // Safe because `from_utf8` just checked this prefix.
+                self.string
+                    .push_str(unsafe { str::from_utf8_unchecked(&rest[..valid]) });
+                written += valid;
+
+                if e.error_len().is_none() {
+                    let tail = &rest[valid..];
+                    self.pending[..tail.len()].copy_from_slice(tail);
+                    self.pending_len = tail.len() as u8;
+                    Ok(buf.len())
+                } else if written > 0 {
+                    // Report the valid prefix; the next call starts at the
+                    // invalid sequence and fails without writing anything.
+                    Ok(written)
+                } else {
+                    Err(invalid_data(e))
+                }
+            }
+        }
+    }
+
+    /// Does nothing: complete characters are appended as soon as they are
+    /// written, and a partial one cannot be.
+    #[inline]
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+/// A `Read + BufRead + Seek` cursor over the bytes of a string.
+///
+/// The string can be owned or borrowed: anything that is `AsRef<str>`, such as
+/// `InlinableString` or `&InlinableString`, works without copying it into a
+/// `Vec<u8>` first.
+///
+/// # Examples
+///
+/// ```
+/// use inlinable_string::InlinableString;
+/// use inlinable_string::io::Reader;
+/// use std::io::{Read, Seek, SeekFrom};
+///
+/// let mut reader = Reader::new(InlinableString::from("key=value"));
+/// reader.seek(SeekFrom::Start(4)).unwrap();
+/// let mut value = String::new();
+/// reader.read_to_string(&mut value).unwrap();
+/// assert_eq!(value, "value");
+/// ```
+#[derive(Clone, Debug)]
+pub struct Reader<S> {
+    cursor: io::Cursor<Bytes<S>>,
+}
+
+/// Views a string as its bytes so that `io::Cursor` can do the reading.
+#[derive(Clone, Debug)]
+struct Bytes<S>(S);
+
+impl<S: AsRef<str>> AsRef<[u8]> for Bytes<S> {
+    #[inline]
+    fn as_ref(&self) -> &[u8] {
+        self.0.as_ref().as_bytes()
+    }
+}
+
+impl<S: AsRef<str>> Reader<S> {
+    /// Creates a reader positioned at the start of `string`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use inlinable_string::InlinableString;
+    /// use inlinable_string::io::Reader;
+    /// use std::io::BufRead;
+    ///
+    /// let s = InlinableString::from("one\ntwo\n");
+    /// let lines: Vec<String> = Reader::new(&s).lines().map(Result::unwrap).collect();
+    /// assert_eq!(lines, ["one", "two"]);
+    /// ```
+    #[inline]
+    pub fn new(string: S) -> Reader<S> {
+        Reader {
+            cursor: io::Cursor::new(Bytes(string)),
+        }
+    }
+
+    /// Returns the current byte offset into the string.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use inlinable_string::InlinableString;
+    /// use inlinable_string::io::Reader;
+    /// use std::io::Read;
+    ///
+    /// let mut reader = Reader::new(InlinableString::from("abc"));
+    /// reader.read_exact(&mut [0; 2]).unwrap();
+    /// assert_eq!(reader.position(), 2);
+    /// ```
+    #[inline]
+    pub fn position(&self) -> u64 {
+        self.cursor.position()
+    }
+
+    /// Returns a reference to the underlying string.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use inlinable_string::InlinableString;
+    /// use inlinable_string::io::Reader;
+    ///
+    /// let reader = Reader::new(InlinableString::from("abc"));
+    /// assert_eq!(reader.get_ref(), "abc");
+    /// ```
+    #[inline]
+    pub fn get_ref(&self) -> &S {
+        &self.cursor.get_ref().0
+    }
+
+    /// Consumes the reader, returning the underlying string.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use inlinable_string::InlinableString;
+    /// use inlinable_string::io::Reader;
+    ///
+    /// let reader = Reader::new(InlinableString::from("abc"));
+    /// assert_eq!(reader.into_inner(), "abc");
+    /// ```
+    #[inline]
+    pub fn into_inner(self) -> S {
+        self.cursor.into_inner().0
+    }
+}
+
+impl<S: AsRef<str>> Read for Reader<S> {
+    #[inline]
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        self.cursor.read(buf)
+    }
+}
+
+impl<S: AsRef<str>> BufRead for Reader<S> {
+    #[inline]
+    fn fill_buf(&mut self) -> io::Result<&[u8]> {
+        self.cursor.fill_buf()
+    }
+
+    #[inline]
+    fn consume(&mut self, amt: usize) {
+        self.cursor.consume(amt)
+    }
+}
+
+impl<S: AsRef<str>> Seek for Reader<S> {
+    #[inline]
+    fn seek(&mut self, pos: SeekFrom) -> io::Result<u64> {
+        self.cursor.seek(pos)
+    }
+}
+
+/// Returns the length of the UTF-8 sequence introduced by `lead`, which must
+/// be a valid leading byte.
+#[inline]
+fn utf8_width(lead: u8) -> usize {
+    match lead {
+        0x00..=0x7f => 1,
+        0xc0..=0xdf => 2,
+        0xe0..=0xef => 3,
+        _ => 4,
+    }
+}
+
+fn invalid_data(e: str::Utf8Error) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, e)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Reader, Utf8Writer};
+    use crate::InlinableString;
+    use std::fmt::Write as _;
+    use std::io::{BufWriter, ErrorKind, Write};
+
+    #[test]
+    fn test_write_macro_is_not_ambiguous() {
+        let mut s = InlinableString::new();
+        write!(s, "{}-", 1).unwrap();
+        let accent = 'é';
+        write!(Utf8Writer::new(&mut s), "{}", accent).unwrap();
+        assert_eq!(s, "1-é");
+    }
+
+    #[test]
+    fn test_utf8_writer_split_characters() {
+        let input = "aé€😀 and a tail long enough to move to the heap";
+        for chunk in 1..5 {
+            let mut s = InlinableString::new();
+            let mut writer = Utf8Writer::new(&mut s);
+            for bytes in input.as_bytes().chunks(chunk) {
+                writer.write_all(bytes).unwrap();
+            }
+            writer.flush().unwrap();
+            writer.finish().unwrap();
+            assert_eq!(s, input);
+            assert!(s.is_heap());
+        }
+
+        let mut s = InlinableString::new();
+        {
+            let mut writer = BufWriter::with_capacity(3, Utf8Writer::new(&mut s));
+            writer.write_all("😀€".as_bytes()).unwrap();
+            writer.flush().unwrap();
+        }
+        assert_eq!(s, "😀€");
+    }
+
+    #[test]
+    fn test_utf8_writer_invalid() {
+        let mut s = InlinableString::new();
+        let mut writer = Utf8Writer::new(&mut s);
+
+        assert_eq!(writer.write(b"ok\xff!").unwrap(), 2);
+        let err = writer.write(b"\xff!").unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::InvalidData);
+        assert_eq!(writer.write_all(b"ok\xff").unwrap_err().kind(), ErrorKind::InvalidData);
+
+        let mut writer = Utf8Writer::new(&mut s);
+        writer.write_all(b"\xe2\x82").unwrap();
+        let err = writer.write(b"x").unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::InvalidData);
+        assert_eq!(writer.pending(), b"\xe2\x82");
+        writer.write_all(b"\xac").unwrap();
+        writer.finish().unwrap();
+        assert_eq!(s, "okok€");
+
+        let mut writer = Utf8Writer::new(&mut s);
+        writer.write_all(b"\xf0\x9f").unwrap();
+        assert_eq!(writer.finish().unwrap_err().kind(), ErrorKind::UnexpectedEof);
+        assert_eq!(s, "okok€");
+    }
+
+    #[test]
+    fn test_reader() {
+        use std::io::{BufRead, Read, Seek, SeekFrom};
+
+        let long = InlinableString::from("first line\nsecond line, long enough for the heap\n");
+        let mut reader = Reader::new(&long);
+        let mut line = String::new();
+        reader.read_line(&mut line).unwrap();
+        assert_eq!(line, "first line\n");
+
+        reader.seek(SeekFrom::End(-5)).unwrap();
+        let mut rest = String::new();
+        reader.read_to_string(&mut rest).unwrap();
+        assert_eq!(rest, "heap\n");
+
+        let mut reader = Reader::new(InlinableString::from("inline"));
+        let mut buf = [0; 3];
+        reader.read_exact(&mut buf).unwrap();
+        assert_eq!(&buf, b"inl");
+        assert_eq!(reader.into_inner(), "inline");
+    }
+}
@@ -18,7 +18,7 @@
 //! deprecated methods are not included. A `StringExt` implementation is
 //! provided for both `std::string::String` and `InlinableString`. This enables
 //! `InlinableString` to generally work as a drop-in replacement for
-//! `std::string::String` and `&StringExt` to work with references to either
+//! `std::string::String` and `&dyn StringExt` to work with references to either
 //! type.
 //!
 //! # Examples
@@ -28,16 +28,16 @@
 //!
 //! // Small strings are stored inline and don't perform heap-allocation.
 //! let mut s = InlinableString::from("small");
-//! assert_eq!(s.capacity(), inlinable_string::INLINE_STRING_CAPACITY);
+//! assert_eq!(s.capacity(), InlinableString::INLINE_CAPACITY);
 //!
 //! // Inline strings are transparently promoted to heap-allocated strings when
 //! // they grow too big.
-//! s.push_str("a really long string that's bigger than `INLINE_STRING_CAPACITY`");
-//! assert!(s.capacity() > inlinable_string::INLINE_STRING_CAPACITY);
+//! s.push_str("a really long string that's bigger than `InlinableString::INLINE_CAPACITY`");
+//! assert!(s.capacity() > InlinableString::INLINE_CAPACITY);
 //!
 //! // This method can work on strings potentially stored inline on the stack,
 //! // on the heap, or plain old `std::string::String`s!
-//! fn takes_a_string_reference(string: &mut StringExt) {
+//! fn takes_a_string_reference(string: &mut dyn StringExt) {
 //!    // Do something with the string...
 //!    string.push_str("it works!");
 //! }
@@ -53,18 +53,18 @@
 //! # Porting Your Code
 //!
 //! * If `my_string` is always on the stack: `let my_string = String::new();` →
-//! `let my_string = InlinableString::new();`
+//!   `let my_string = InlinableString::new();`
 //!
-//! * `fn foo(string: &mut String) { ... }` → `fn foo(string: &mut StringExt) { ... }`
+//! * `fn foo(string: &mut String) { ... }` → `fn foo(string: &mut dyn StringExt) { ... }`
 //!
 //! * `fn foo(string: &str) { ... }` does not need to be modified.
 //!
 //! * `struct S { member: String }` is a little trickier. If `S` is always stack
-//! allocated, it probably makes sense to make `member` be of type
-//! `InlinableString`. If `S` is heap-allocated and `member` is *always* small,
-//! consider using the more restrictive
-//! [`InlineString`](./inline_string/struct.InlineString.html) type. If `member` is
-//! not always small, then it should probably be left as a `String`.
+//!   allocated, it probably makes sense to make `member` be of type
+//!   `InlinableString`. If `S` is heap-allocated and `member` is *always* small,
+//!   consider using the more restrictive
+//!   [`InlineString`](./inline_string/struct.InlineString.html) type. If `member` is
+//!   not always small, then it should probably be left as a `String`.
 //!
 //! # Serialization
 //!
@@ -72,32 +72,143 @@
 //! Add the `serde` feature to your `Cargo.toml` to enable serialization.
 //!
 //! [serde-docs]: https://serde.rs
+//!
+//! Add the `schemars` feature to implement [`schemars`][schemars-docs]'s
+//! `JsonSchema` trait, so API types holding these strings can generate JSON
+//! schemas. `InlinableString` has the same schema as `String`, and
+//! `InlineString` adds a `maxLength` of `InlineString::CAPACITY`.
+//!
+//! [schemars-docs]: https://docs.rs/schemars
+//!
+//! # Random Strings
+//!
+//! Add the `rand` feature for `InlineString::random_alphanumeric` and the
+//! `AlphanumericString` distribution, which generate short random ids and
+//! tokens directly into inline storage.
+//!
+//! # Buffer Pooling
+//!
+//! Add the `pool` feature to have strings that outgrow inline storage reuse
+//! heap buffers from a thread-local [`StringPool`](./pool/struct.StringPool.html)
+//! instead of allocating fresh ones. The pool is thread-local and needs
+//! `std`, so combined with `no_std` the `pool` feature has no effect.
+//!
+//! # String Sets
+//!
+//! Add the `set` feature for [`InlineStringSet`](./set/struct.InlineStringSet.html),
+//! a sorted set that front-codes its keys so that large numbers of similar
+//! identifiers are mostly stored inline.
+//!
+//! # Model Testing
+//!
+//! Add the `test-util` feature for the [`test_util`](./test_util/index.html)
+//! module, whose `Op` sequences check any `StringExt` implementation against
+//! `String`.
+//!
+//! # Fuzzing
+//!
+//! Add the `arbitrary` feature to implement [`arbitrary`][arbitrary-docs]'s
+//! `Arbitrary` trait for `InlinableString` and `InlineString`, so types that
+//! hold them can derive it. Generated `InlinableString`s cover both the
+//! inline and the heap variant.
+//!
+//! [arbitrary-docs]: https://docs.rs/arbitrary
+//!
+//! Similarly, the `quickcheck` feature implements
+//! [`quickcheck`][quickcheck-docs]'s `Arbitrary` trait for both types. Heap
+//! strings that fit inline shrink to inline ones first, so shrinking finds
+//! bugs at the inline/heap boundary.
+//!
+//! [quickcheck-docs]: https://docs.rs/quickcheck
+//!
+//! For [`proptest`][proptest-docs], the `proptest` feature implements its
+//! `Arbitrary` trait for both types and adds the
+//! [`strategy`](./strategy/index.html) module, with strategies for strings
+//! matching a regular expression or straddling the inline capacity.
+//!
+//! [proptest-docs]: https://docs.rs/proptest
+//!
+//! # Zero-Copy Layout
+//!
+//! `InlineString` has a fixed, documented byte layout with a zeroed tail. Add
+//! the `zerocopy` feature to view it as bytes with `zerocopy::IntoBytes`, and
+//! to validate bytes back into one with `InlineString::try_ref_from_bytes`.
+//!
+//! # Nightly
+//!
+//! With the `nightly` feature, `&InlinableString` and `&InlineString`
+//! implement the unstable `core::str::pattern::Pattern` trait, so they can be
+//! passed directly to `str::find`, `str::split`, and friends.
 
 #![forbid(missing_docs)]
-#![cfg_attr(feature = "nightly", feature(plugin))]
 #![cfg_attr(all(test, feature = "nightly"), feature(test))]
+#![cfg_attr(feature = "nightly", feature(pattern))]
 #![cfg_attr(feature = "no_std", no_std)]
 
-#[allow(unused_imports)]
-#[cfg_attr(feature = "no_std", macro_use)]
+#[cfg_attr(feature = "no_std", allow(unused_imports), macro_use)]
 extern crate alloc;
 
 #[cfg(test)]
 #[cfg(feature = "nightly")]
 extern crate test;
 
+#[cfg(feature = "arbitrary")]
+mod arbitrary_impl;
+#[cfg(feature = "nightly")]
+mod pattern_impl;
+#[cfg(feature = "quickcheck")]
+mod quickcheck_impl;
+#[cfg(feature = "rand")]
+mod rand_impl;
+#[cfg(feature = "schemars")]
+mod schemars_impl;
 #[cfg(feature = "serde")]
 mod serde_impl;
+#[cfg(not(feature = "no_std"))]
+mod std_impl;
+#[cfg(feature = "zerocopy")]
+mod zerocopy_impl;
 
+pub mod drain;
+pub mod error;
 pub mod inline_string;
+#[cfg(not(feature = "no_std"))]
+pub mod io;
+#[cfg(all(feature = "pool", not(feature = "no_std")))]
+pub mod pool;
+#[cfg(feature = "set")]
+pub mod set;
+pub mod spare;
+#[cfg(feature = "proptest")]
+pub mod strategy;
 pub mod string_ext;
-
-pub use inline_string::{InlineString, INLINE_STRING_CAPACITY};
+#[cfg(all(feature = "test-util", not(feature = "no_std")))]
+pub mod test_util;
+pub mod try_string_ext;
+#[cfg(not(feature = "no_std"))]
+pub mod wire;
+
+pub use drain::Drain;
+pub use error::Error;
+#[allow(deprecated)]
+pub use inline_string::INLINE_STRING_CAPACITY;
+pub use inline_string::{AsciiPredicate, InlinePushable, InlineString};
+#[cfg(all(feature = "pool", not(feature = "no_std")))]
+pub use pool::StringPool;
+#[cfg(feature = "rand")]
+pub use rand_impl::AlphanumericString;
+#[cfg(feature = "set")]
+pub use set::InlineStringSet;
+pub use spare::SpareCapacity;
 pub use string_ext::StringExt;
+pub use try_string_ext::TryStringExt;
 
-use alloc::borrow::{Borrow, Cow};
+use alloc::borrow::{Borrow, BorrowMut, Cow};
+use alloc::boxed::Box;
+use alloc::collections::TryReserveError;
 use alloc::vec::Vec;
 use alloc::string::{FromUtf16Error, FromUtf8Error, String};
+use inline_string::NotEnoughSpaceError;
 use core::cmp::Ordering;
 use core::convert;
 use core::fmt;
@@ -111,7 +222,7 @@ use core::str::FromStr;
 /// stack.
 ///
 /// See the [module level documentation](./index.html) for more.
-#[derive(Clone, Eq)]
+#[derive(Eq)]
 pub enum InlinableString {
     /// A heap-allocated string.
     Heap(String),
@@ -119,9 +230,33 @@ pub enum InlinableString {
     Inline(InlineString),
 }
 
+impl Clone for InlinableString {
+    fn clone(&self) -> Self {
+        match *self {
+            InlinableString::Heap(ref s) => InlinableString::Heap(s.clone()),
+            InlinableString::Inline(ref s) => InlinableString::Inline(s.clone()),
+        }
+    }
+
+    /// Copies `source` into `self`, reusing `self`'s heap buffer if it has
+    /// one, even when `source` is stored inline.
+    fn clone_from(&mut self, source: &Self) {
+        match *self {
+            InlinableString::Heap(ref mut dst) => match *source {
+                InlinableString::Heap(ref src) => dst.clone_from(src),
+                InlinableString::Inline(ref src) => {
+                    dst.clear();
+                    dst.push_str(src);
+                }
+            },
+            InlinableString::Inline(_) => *self = source.clone(),
+        }
+    }
+}
+
 impl fmt::Debug for InlinableString {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        fmt::Debug::fmt(&self as &str, f)
+        fmt::Debug::fmt(self as &str, f)
     }
 }
 
@@ -141,6 +276,78 @@ impl<'a> iter::FromIterator<&'a str> for InlinableString {
     }
 }
 
+impl iter::FromIterator<String> for InlinableString {
+    fn from_iter<I: IntoIterator<Item = String>>(iter: I) -> InlinableString {
+        let mut iterator = iter.into_iter();
+        match iterator.next() {
+            None => InlinableString::new(),
+            Some(first) => {
+                // Reuse the first string's buffer if it is already too big to
+                // be stored inline.
+                let mut buf = InlinableString::from(first);
+                buf.extend(iterator);
+                buf
+            }
+        }
+    }
+}
+
+impl<'a> iter::FromIterator<Cow<'a, str>> for InlinableString {
+    fn from_iter<I: IntoIterator<Item = Cow<'a, str>>>(iter: I) -> InlinableString {
+        let mut buf = InlinableString::new();
+        buf.extend(iter);
+        buf
+    }
+}
+
+impl iter::FromIterator<Box<str>> for InlinableString {
+    fn from_iter<I: IntoIterator<Item = Box<str>>>(iter: I) -> InlinableString {
+        let mut iterator = iter.into_iter();
+        match iterator.next() {
+            None => InlinableString::new(),
+            Some(first) => {
+                let mut buf = InlinableString::from(first);
+                buf.extend(iterator);
+                buf
+            }
+        }
+    }
+}
+
+impl iter::Sum<InlinableString> for InlinableString {
+    fn sum<I: Iterator<Item = InlinableString>>(mut iter: I) -> InlinableString {
+        // Reuse the first string's storage (and any heap capacity it already
+        // has) as the accumulator.
+        let mut buf = iter.next().unwrap_or_default();
+        for s in iter {
+            buf.push_str(&s);
+        }
+        buf
+    }
+}
+
+impl<'a> iter::Sum<&'a InlinableString> for InlinableString {
+    fn sum<I: Iterator<Item = &'a InlinableString>>(iter: I) -> InlinableString {
+        let mut buf = InlinableString::new();
+        for s in iter {
+            buf.push_str(s);
+        }
+        buf
+    }
+}
+
+impl<'a> iter::Sum<&'a str> for InlinableString {
+    fn sum<I: Iterator<Item = &'a str>>(iter: I) -> InlinableString {
+        iter.collect()
+    }
+}
+
+impl iter::Sum<String> for InlinableString {
+    fn sum<I: Iterator<Item = String>>(iter: I) -> InlinableString {
+        iter.collect()
+    }
+}
+
 impl Extend<char> for InlinableString {
     fn extend<I: IntoIterator<Item = char>>(&mut self, iterable: I) {
         let iterator = iterable.into_iter();
@@ -160,16 +367,52 @@ impl<'a> Extend<&'a char> for InlinableString {
 
 impl<'a> Extend<&'a str> for InlinableString {
     fn extend<I: IntoIterator<Item = &'a str>>(&mut self, iterable: I) {
-        let iterator = iterable.into_iter();
-        let (lower_bound, _) = iterator.size_hint();
-        self.reserve(lower_bound);
-        for s in iterator {
-            self.push_str(s);
+        // The size hint counts slices, not bytes, so it says nothing about how
+        // much to reserve. Instead, buffer a chunk of slices at a time and
+        // reserve their exact combined length, so each chunk grows (or
+        // promotes) the string at most once.
+        const CHUNK_LEN: usize = 16;
+        let mut iterator = iterable.into_iter();
+        let mut chunk = [""; CHUNK_LEN];
+        loop {
+            let mut len = 0;
+            for s in iterator.by_ref().take(CHUNK_LEN) {
+                chunk[len] = s;
+                len += 1;
+            }
+            self.extend_from_slices(&chunk[..len]);
+            if len < CHUNK_LEN {
+                return;
+            }
+        }
+    }
+}
+
+impl Extend<String> for InlinableString {
+    fn extend<I: IntoIterator<Item = String>>(&mut self, iterable: I) {
+        for s in iterable {
+            self.push_str(&s);
+        }
+    }
+}
+
+impl<'a> Extend<Cow<'a, str>> for InlinableString {
+    fn extend<I: IntoIterator<Item = Cow<'a, str>>>(&mut self, iterable: I) {
+        for s in iterable {
+            self.push_str(&s);
         }
     }
 }
 
-impl<'a> ops::Add<&'a str> for InlinableString {
+impl Extend<Box<str>> for InlinableString {
+    fn extend<I: IntoIterator<Item = Box<str>>>(&mut self, iterable: I) {
+        for s in iterable {
+            self.push_str(&s);
+        }
+    }
+}
+
+impl ops::Add<&str> for InlinableString {
     type Output = InlinableString;
 
     #[inline]
@@ -179,6 +422,23 @@ impl<'a> ops::Add<&'a str> for InlinableString {
     }
 }
 
+impl ops::Add<char> for InlinableString {
+    type Output = InlinableString;
+
+    #[inline]
+    fn add(mut self, other: char) -> InlinableString {
+        self.push(other);
+        self
+    }
+}
+
+impl ops::AddAssign<&str> for InlinableString {
+    #[inline]
+    fn add_assign(&mut self, other: &str) {
+        self.push_str(other);
+    }
+}
+
 impl PartialOrd<InlinableString> for InlinableString {
     fn partial_cmp(&self, rhs: &InlinableString) -> Option<Ordering> {
         Some(Ord::cmp(&self[..], &rhs[..]))
@@ -201,15 +461,21 @@ impl hash::Hash for InlinableString {
 
 impl Borrow<str> for InlinableString {
     fn borrow(&self) -> &str {
-        &*self
+        self
+    }
+}
+
+impl BorrowMut<str> for InlinableString {
+    fn borrow_mut(&mut self) -> &mut str {
+        self
     }
 }
 
 impl AsRef<str> for InlinableString {
     fn as_ref(&self) -> &str {
         match *self {
-            InlinableString::Heap(ref s) => &*s,
-            InlinableString::Inline(ref s) => &*s,
+            InlinableString::Heap(ref s) => s,
+            InlinableString::Inline(ref s) => s,
         }
     }
 }
@@ -226,7 +492,7 @@ impl AsMut<str> for InlinableString {
 impl<'a> From<&'a str> for InlinableString {
     #[inline]
     fn from(string: &'a str) -> InlinableString {
-        if string.len() <= INLINE_STRING_CAPACITY {
+        if string.len() <= InlineString::CAPACITY {
             InlinableString::Inline(string.into())
         } else {
             InlinableString::Heap(string.into())
@@ -234,10 +500,31 @@ impl<'a> From<&'a str> for InlinableString {
     }
 }
 
+impl From<InlineString> for InlinableString {
+    #[inline]
+    fn from(string: InlineString) -> InlinableString {
+        InlinableString::Inline(string)
+    }
+}
+
+/// Fails with `NotEnoughSpaceError` if the string is too long to be stored
+/// inline. Use `InlinableString::try_into_inline` to get the string back on
+/// failure.
+impl convert::TryFrom<InlinableString> for InlineString {
+    type Error = NotEnoughSpaceError;
+
+    #[inline]
+    fn try_from(string: InlinableString) -> Result<InlineString, NotEnoughSpaceError> {
+        string
+            .try_into_inline()
+            .map_err(|string| NotEnoughSpaceError::new(string.len(), InlineString::CAPACITY))
+    }
+}
+
 impl From<String> for InlinableString {
     #[inline]
     fn from(string: String) -> InlinableString {
-        if string.len() <= INLINE_STRING_CAPACITY {
+        if string.len() <= InlineString::CAPACITY {
             InlinableString::Inline(string.as_str().into())
         } else {
             InlinableString::Heap(string)
@@ -245,6 +532,62 @@ impl From<String> for InlinableString {
     }
 }
 
+impl From<Box<str>> for InlinableString {
+    #[inline]
+    fn from(string: Box<str>) -> InlinableString {
+        if string.len() <= InlineString::CAPACITY {
+            InlinableString::Inline(InlineString::from(&*string))
+        } else {
+            InlinableString::Heap(String::from(string))
+        }
+    }
+}
+
+impl From<InlinableString> for Box<str> {
+    #[inline]
+    fn from(string: InlinableString) -> Box<str> {
+        match string {
+            InlinableString::Heap(s) => s.into_boxed_str(),
+            InlinableString::Inline(s) => Box::from(&*s),
+        }
+    }
+}
+
+impl From<InlinableString> for String {
+    #[inline]
+    fn from(string: InlinableString) -> String {
+        match string {
+            InlinableString::Heap(s) => s,
+            InlinableString::Inline(s) => String::from(&*s),
+        }
+    }
+}
+
+impl<'a> From<InlinableString> for Cow<'a, str> {
+    #[inline]
+    fn from(string: InlinableString) -> Cow<'a, str> {
+        Cow::Owned(String::from(string))
+    }
+}
+
+/// Validates the bytes as UTF-8, as with `StringExt::from_utf8`. Short
+/// strings are stored inline.
+impl convert::TryFrom<Vec<u8>> for InlinableString {
+    type Error = FromUtf8Error;
+
+    #[inline]
+    fn try_from(vec: Vec<u8>) -> Result<InlinableString, FromUtf8Error> {
+        <InlinableString as StringExt>::from_utf8(vec)
+    }
+}
+
+impl From<InlinableString> for Vec<u8> {
+    #[inline]
+    fn from(string: InlinableString) -> Vec<u8> {
+        string.into_bytes()
+    }
+}
+
 impl FromStr for InlinableString {
     type Err = convert::Infallible;
 
@@ -393,7 +736,10 @@ impl ops::DerefMut for InlinableString {
 impl PartialEq<InlinableString> for InlinableString {
     #[inline]
     fn eq(&self, rhs: &InlinableString) -> bool {
-        PartialEq::eq(&self[..], &rhs[..])
+        match (self, rhs) {
+            (InlinableString::Inline(a), InlinableString::Inline(b)) => a == b,
+            _ => PartialEq::eq(&self[..], &rhs[..]),
+        }
     }
 }
 
@@ -421,149 +767,1143 @@ impl_eq! { InlinableString, &'a str }
 impl_eq! { InlinableString, InlineString }
 impl_eq! { Cow<'a, str>, InlinableString }
 
-impl<'a> StringExt<'a> for InlinableString {
-    #[inline]
-    fn new() -> Self {
-        InlinableString::Inline(InlineString::new())
-    }
+macro_rules! impl_ord {
+    ($lhs:ty, $rhs: ty) => {
+        impl<'a> PartialOrd<$rhs> for $lhs {
+            #[inline]
+            fn partial_cmp(&self, other: &$rhs) -> Option<Ordering> {
+                PartialOrd::partial_cmp(&self[..], &other[..])
+            }
+        }
 
-    #[inline]
-    fn with_capacity(capacity: usize) -> Self {
-        if capacity <= INLINE_STRING_CAPACITY {
-            InlinableString::Inline(InlineString::new())
-        } else {
-            InlinableString::Heap(String::with_capacity(capacity))
+        impl<'a> PartialOrd<$lhs> for $rhs {
+            #[inline]
+            fn partial_cmp(&self, other: &$lhs) -> Option<Ordering> {
+                PartialOrd::partial_cmp(&self[..], &other[..])
+            }
         }
-    }
+    };
+}
 
-    #[inline]
-    fn from_utf8(vec: Vec<u8>) -> Result<Self, FromUtf8Error> {
-        String::from_utf8(vec).map(InlinableString::Heap)
+impl_ord! { InlinableString, str }
+impl_ord! { InlinableString, String }
+impl_ord! { InlinableString, &'a str }
+impl_ord! { InlinableString, InlineString }
+impl_ord! { Cow<'a, str>, InlinableString }
+
+/// Allocates the buffer for a string being promoted to the heap, reusing one
+/// from the thread's `StringPool` when the `pool` feature is enabled.
+#[inline]
+fn heap_buffer(capacity: usize) -> String {
+    #[cfg(all(feature = "pool", not(feature = "no_std")))]
+    {
+        StringPool::take(capacity)
     }
+    #[cfg(not(all(feature = "pool", not(feature = "no_std"))))]
+    {
+        String::with_capacity(capacity)
+    }
+}
 
-    #[inline]
-    fn from_utf16(v: &[u16]) -> Result<Self, FromUtf16Error> {
-        String::from_utf16(v).map(InlinableString::Heap)
+/// Like `heap_buffer`, but reports allocation failure instead of aborting.
+#[inline]
+fn try_heap_buffer(capacity: usize) -> Result<String, TryReserveError> {
+    #[cfg(all(feature = "pool", not(feature = "no_std")))]
+    {
+        StringPool::try_take(capacity)
     }
+    #[cfg(not(all(feature = "pool", not(feature = "no_std"))))]
+    {
+        let mut buffer = String::new();
+        buffer.try_reserve_exact(capacity)?;
+        Ok(buffer)
+    }
+}
 
-    #[inline]
-    fn from_utf16_lossy(v: &[u16]) -> Self {
-        InlinableString::Heap(String::from_utf16_lossy(v))
+/// Disposes of a string that is no longer needed, returning its heap buffer
+/// to the thread's `StringPool` when the `pool` feature is enabled.
+#[inline]
+fn recycle_heap_buffer(string: InlinableString) {
+    #[cfg(all(feature = "pool", not(feature = "no_std")))]
+    {
+        if let InlinableString::Heap(s) = string {
+            StringPool::give(s);
+        }
+    }
+    #[cfg(not(all(feature = "pool", not(feature = "no_std"))))]
+    {
+        drop(string);
     }
+}
 
-    #[inline]
-    unsafe fn from_raw_parts(buf: *mut u8, length: usize, capacity: usize) -> Self {
-        InlinableString::Heap(String::from_raw_parts(buf, length, capacity))
+/// Decodes `v` as UTF-16 directly into inline storage, returning `None` if
+/// the result does not fit. Unpaired surrogates are replaced with U+FFFD when
+/// `lossy` is set, and also cause `None` to be returned otherwise.
+fn decode_utf16_inline(v: &[u16], lossy: bool) -> Option<InlineString> {
+    // Every code unit decodes to at least one byte of UTF-8.
+    if v.len() > InlineString::CAPACITY {
+        return None;
+    }
+    let mut string = InlineString::new();
+    for ch in char::decode_utf16(v.iter().cloned()) {
+        let ch = match ch {
+            Ok(ch) => ch,
+            Err(_) if lossy => char::REPLACEMENT_CHARACTER,
+            Err(_) => return None,
+        };
+        string.push(ch).ok()?;
     }
+    Some(string)
+}
 
+impl InlinableString {
+    /// The largest length (in bytes) at which an `InlinableString` can be
+    /// stored inline. Equal to `InlineString::CAPACITY`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use inlinable_string::{InlinableString, InlineString, StringExt};
+    ///
+    /// assert_eq!(InlinableString::INLINE_CAPACITY, InlineString::CAPACITY);
+    /// assert_eq!(InlinableString::new().capacity(), InlinableString::INLINE_CAPACITY);
+    /// ```
+    pub const INLINE_CAPACITY: usize = InlineString::CAPACITY;
+
+    /// Creates a new, empty string, stored inline.
+    ///
+    /// Unlike `StringExt::new`, this is a `const fn`, so it can be used to
+    /// initialize statics and constants.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use inlinable_string::InlinableString;
+    ///
+    /// static DEFAULT_NAME: InlinableString = InlinableString::new();
+    /// assert!(DEFAULT_NAME.is_empty());
+    /// assert!(DEFAULT_NAME.is_inline());
+    /// ```
     #[inline]
-    unsafe fn from_utf8_unchecked(bytes: Vec<u8>) -> Self {
-        InlinableString::Heap(String::from_utf8_unchecked(bytes))
+    pub const fn new() -> InlinableString {
+        InlinableString::Inline(InlineString::new())
     }
 
+    /// Converts a slice of bytes to a string, replacing invalid UTF-8
+    /// sequences with U+FFFD REPLACEMENT CHARACTER, as with
+    /// `String::from_utf8_lossy`.
+    ///
+    /// Unlike `StringExt::from_utf8_lossy`, this always returns an owned
+    /// string, which is stored inline without allocating whenever the result
+    /// fits.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use inlinable_string::InlinableString;
+    ///
+    /// let s = InlinableString::from_utf8_lossy_owned(b"Hello \xF0\x90\x80World");
+    /// assert_eq!(s, "Hello \u{FFFD}World");
+    /// assert!(s.is_inline());
+    /// ```
+    pub fn from_utf8_lossy_owned(v: &[u8]) -> InlinableString {
+        // Each invalid sequence is replaced by a character at least as long,
+        // so the result is never shorter than the input.
+        let mut string = InlinableString::with_capacity(v.len());
+        for chunk in v.utf8_chunks() {
+            string.push_str(chunk.valid());
+            if !chunk.invalid().is_empty() {
+                string.push(char::REPLACEMENT_CHARACTER);
+            }
+        }
+        string
+    }
+
+    /// Creates a string from pre-compiled format arguments, as built by
+    /// `format_args!`. Unlike `format!`, the result is stored inline when it
+    /// fits.
+    ///
+    /// # Panics
+    ///
+    /// Panics if a formatting trait implementation returns an error, as
+    /// `alloc::fmt::format` does.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use inlinable_string::InlinableString;
+    ///
+    /// let s = InlinableString::from_fmt(format_args!("{}-{:03}", "id", 7));
+    /// assert_eq!(s, "id-007");
+    /// assert!(s.is_inline());
+    /// ```
+    pub fn from_fmt(args: fmt::Arguments<'_>) -> InlinableString {
+        if let Some(s) = args.as_str() {
+            return InlinableString::from(s);
+        }
+        let mut string = InlinableString::new();
+        fmt::Write::write_fmt(&mut string, args)
+            .expect("a formatting trait implementation returned an error unexpectedly");
+        string
+    }
+
+    /// Returns true if this string is stored inline, without a heap
+    /// allocation.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use inlinable_string::{InlinableString, StringExt};
+    ///
+    /// let mut s = InlinableString::from("small");
+    /// assert!(s.is_inline());
+    /// s.push_str(" but now big enough to need the heap");
+    /// assert!(!s.is_inline());
+    /// ```
     #[inline]
-    fn into_bytes(self) -> Vec<u8> {
-        match self {
-            InlinableString::Heap(s) => s.into_bytes(),
-            InlinableString::Inline(s) => Vec::from(&s[..]),
+    pub fn is_inline(&self) -> bool {
+        match *self {
+            InlinableString::Heap(_) => false,
+            InlinableString::Inline(_) => true,
         }
     }
 
+    /// Returns true if this string has been promoted to heap storage.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use inlinable_string::{InlinableString, StringExt};
+    ///
+    /// let mut s = InlinableString::from("small");
+    /// assert!(!s.is_heap());
+    /// s.push_str(" but now big enough to need the heap");
+    /// assert!(s.is_heap());
+    /// ```
     #[inline]
-    fn push_str(&mut self, string: &str) {
-        let promoted = match *self {
-            InlinableString::Inline(ref mut s) => {
-                if s.push_str(string).is_ok() {
-                    return;
-                }
-                let mut promoted = String::with_capacity(string.len() + s.len());
-                promoted.push_str(&*s);
-                promoted.push_str(string);
-                promoted
+    pub fn is_heap(&self) -> bool {
+        !self.is_inline()
+    }
+
+    /// Moves a heap-allocated string into inline storage if it fits,
+    /// releasing its heap buffer. Returns true if the string is stored inline
+    /// afterwards, whether or not it had to be moved.
+    ///
+    /// Unlike `shrink_to_fit`, this leaves the capacity of a string that is
+    /// too long to be stored inline untouched.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use inlinable_string::{InlinableString, StringExt};
+    ///
+    /// let mut s = InlinableString::from("a string that is too long to be stored inline");
+    /// let capacity = s.capacity();
+    /// assert!(!s.make_inline());
+    /// assert_eq!(s.capacity(), capacity);
+    ///
+    /// s.truncate(8);
+    /// assert!(s.make_inline());
+    /// assert_eq!(s, "a string");
+    /// ```
+    pub fn make_inline(&mut self) -> bool {
+        let demoted = match *self {
+            InlinableString::Inline(_) => return true,
+            InlinableString::Heap(ref s) if s.len() <= InlineString::CAPACITY => {
+                InlineString::from(&s[..])
             }
+            InlinableString::Heap(_) => return false,
+        };
+        let old = mem::replace(self, InlinableString::Inline(demoted));
+        recycle_heap_buffer(old);
+        true
+    }
+
+    /// Promotes the string to heap storage, if it is not already there, and
+    /// returns the underlying `String`. Room is reserved for at least
+    /// `additional` more bytes.
+    ///
+    /// Use this before appending a lot of text to a string to skip the
+    /// inline capacity checks on every append. To convert the string into a
+    /// `String` outright, use `String::from`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use inlinable_string::InlinableString;
+    ///
+    /// let mut s = InlinableString::from("log:");
+    /// let heap = s.force_heap(256);
+    /// assert!(heap.capacity() >= 260);
+    /// for _ in 0..16 {
+    ///     heap.push_str(" entry");
+    /// }
+    /// assert!(s.is_heap());
+    /// assert!(s.starts_with("log: entry entry"));
+    /// ```
+    pub fn force_heap(&mut self, additional: usize) -> &mut String {
+        if let InlinableString::Inline(ref s) = *self {
+            let mut buffer = heap_buffer(s.len().saturating_add(additional));
+            buffer.push_str(s);
+            *self = InlinableString::Heap(buffer);
+        }
+        match *self {
             InlinableString::Heap(ref mut s) => {
-                s.push_str(string);
-                return;
+                s.reserve(additional);
+                s
             }
-        };
-        mem::swap(self, &mut InlinableString::Heap(promoted));
+            InlinableString::Inline(_) => {
+                panic!("inlinable_string: internal error: this branch should be unreachable")
+            }
+        }
     }
 
+    /// Returns the number of bytes that can be appended to this string before
+    /// it must grow its storage.
+    ///
+    /// For an inline string, this is how many more bytes fit before it is
+    /// promoted to the heap. For a heap-allocated string, it is how many more
+    /// bytes fit before its buffer is reallocated. Heap-allocated strings are
+    /// never demoted by appending, so this may exceed what would fit inline.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use inlinable_string::InlinableString;
+    ///
+    /// let s = InlinableString::from("abc");
+    /// assert_eq!(s.remaining_capacity(), InlinableString::INLINE_CAPACITY - 3);
+    /// ```
     #[inline]
-    fn capacity(&self) -> usize {
+    pub fn remaining_capacity(&self) -> usize {
         match *self {
-            InlinableString::Heap(ref s) => s.capacity(),
-            InlinableString::Inline(_) => INLINE_STRING_CAPACITY,
+            InlinableString::Heap(ref s) => s.capacity() - s.len(),
+            InlinableString::Inline(ref s) => s.remaining_capacity(),
         }
     }
 
+    /// Consumes this string, returning its heap buffer (if any) to the current
+    /// thread's `StringPool` for reuse by later promotions.
+    ///
+    /// This method requires the `pool` feature.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use inlinable_string::{InlinableString, StringExt, StringPool};
+    ///
+    /// StringPool::clear();
+    /// let s = InlinableString::with_capacity(100);
+    /// s.recycle();
+    /// assert_eq!(StringPool::len(), 1);
+    /// ```
+    #[cfg(all(feature = "pool", not(feature = "no_std")))]
     #[inline]
-    fn reserve(&mut self, additional: usize) {
-        let promoted = match *self {
-            InlinableString::Inline(ref s) => {
-                let new_capacity = s.len() + additional;
-                if new_capacity <= INLINE_STRING_CAPACITY {
-                    return;
-                }
-                let mut promoted = String::with_capacity(new_capacity);
-                promoted.push_str(&s);
-                promoted
-            }
-            InlinableString::Heap(ref mut s) => {
-                s.reserve(additional);
-                return;
+    pub fn recycle(self) {
+        recycle_heap_buffer(self);
+    }
+
+    /// Converts this string into an `InlineString` if its contents fit in
+    /// inline storage, or gives it back unchanged if they do not.
+    ///
+    /// A heap-allocated string whose contents fit is copied into inline
+    /// storage and its heap buffer is released.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use inlinable_string::{InlinableString, StringExt};
+    ///
+    /// let mut s = InlinableString::with_capacity(100);
+    /// s.push_str("hello");
+    /// assert_eq!(s.try_into_inline().unwrap(), "hello");
+    ///
+    /// let long = InlinableString::from("a string that is too long to be stored inline");
+    /// let long = long.try_into_inline().unwrap_err();
+    /// assert!(long.is_heap());
+    /// ```
+    pub fn try_into_inline(self) -> Result<InlineString, InlinableString> {
+        match self {
+            InlinableString::Inline(s) => Ok(s),
+            InlinableString::Heap(ref s) if s.len() <= InlineString::CAPACITY => {
+                let inline = InlineString::from(&s[..]);
+                recycle_heap_buffer(self);
+                Ok(inline)
             }
-        };
-        mem::swap(self, &mut InlinableString::Heap(promoted));
+            InlinableString::Heap(_) => Err(self),
+        }
     }
 
-    #[inline]
-    fn reserve_exact(&mut self, additional: usize) {
-        let promoted = match *self {
-            InlinableString::Inline(ref s) => {
-                let new_capacity = s.len() + additional;
-                if new_capacity <= INLINE_STRING_CAPACITY {
-                    return;
-                }
-                let mut promoted = String::with_capacity(new_capacity);
-                promoted.push_str(&s);
-                promoted
+    /// Splits the string in two at byte index `at`, returning everything from
+    /// `at` onwards and keeping everything before it in `self`.
+    ///
+    /// Both halves are stored inline when they fit. In particular, a
+    /// heap-allocated string that is left with a short prefix is demoted to
+    /// inline storage, and its old buffer is released.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `at` is not on a character boundary, or is beyond the end of
+    /// the string.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use inlinable_string::InlinableString;
+    ///
+    /// let mut s = InlinableString::from("key=a value that is too long to be inline");
+    /// let value = s.split_off_shrink(4);
+    /// assert_eq!(s, "key=");
+    /// assert!(s.is_inline());
+    /// assert_eq!(value, "a value that is too long to be inline");
+    /// ```
+    pub fn split_off_shrink(&mut self, at: usize) -> InlinableString {
+        assert!(
+            self.is_char_boundary(at),
+            "inlinable_string::InlinableString::split_off_shrink: index is not a character boundary"
+        );
+        let tail = InlinableString::from(&self[at..]);
+        self.truncate(at);
+        self.make_inline();
+        tail
+    }
+
+    /// Returns the lowercase equivalent of this string, as with
+    /// `str::to_lowercase`, stored inline when the result fits.
+    ///
+    /// `s.to_lowercase()` still reaches `str::to_lowercase` and returns a
+    /// `String`; this method has its own name so it does not shadow it.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use inlinable_string::InlinableString;
+    ///
+    /// let s = InlinableString::from("HELLO Wörld");
+    /// let lower = s.to_lowercase_inlinable();
+    /// assert_eq!(lower, "hello wörld");
+    /// assert!(lower.is_inline());
+    /// ```
+    pub fn to_lowercase_inlinable(&self) -> InlinableString {
+        // Lowercasing 'Σ' depends on the surrounding characters; leave that
+        // to `str::to_lowercase`.
+        if self.contains('Σ') {
+            return InlinableString::from(self.to_lowercase());
+        }
+
+        let mut result = InlinableString::with_capacity(self.len());
+        for ch in self.chars() {
+            for lower in ch.to_lowercase() {
+                result.push(lower);
             }
-            InlinableString::Heap(ref mut s) => {
-                s.reserve_exact(additional);
-                return;
+        }
+        result
+    }
+
+    /// Returns the uppercase equivalent of this string, as with
+    /// `str::to_uppercase`, stored inline when the result fits. See
+    /// `to_lowercase_inlinable` for why this is not called `to_uppercase`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use inlinable_string::InlinableString;
+    ///
+    /// let s = InlinableString::from("straße");
+    /// let upper = s.to_uppercase_inlinable();
+    /// assert_eq!(upper, "STRASSE");
+    /// assert!(upper.is_inline());
+    /// ```
+    pub fn to_uppercase_inlinable(&self) -> InlinableString {
+        let mut result = InlinableString::with_capacity(self.len());
+        for ch in self.chars() {
+            for upper in ch.to_uppercase() {
+                result.push(upper);
             }
-        };
-        mem::swap(self, &mut InlinableString::Heap(promoted));
-    }
-
-    #[inline]
-    fn shrink_to_fit(&mut self) {
-        if self.len() <= INLINE_STRING_CAPACITY {
-            let demoted = if let InlinableString::Heap(ref s) = *self {
-                InlineString::from(&s[..])
-            } else {
-                return;
-            };
-            mem::swap(self, &mut InlinableString::Inline(demoted));
-            return;
         }
+        result
+    }
+
+    /// Returns a copy of this string with ASCII letters lowercased, as with
+    /// `str::to_ascii_lowercase`. The result has the same length, so it is
+    /// stored inline whenever this string fits inline.
+    ///
+    /// Like `to_lowercase_inlinable`, this has its own name so that
+    /// `s.to_ascii_lowercase()` still returns a `String`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use inlinable_string::InlinableString;
+    ///
+    /// let s = InlinableString::from("Content-Type");
+    /// let lower = s.to_ascii_lowercase_inlinable();
+    /// assert_eq!(lower, "content-type");
+    /// assert!(lower.is_inline());
+    /// ```
+    #[inline]
+    pub fn to_ascii_lowercase_inlinable(&self) -> InlinableString {
+        let mut result = self.demoted_clone();
+        result.make_ascii_lowercase();
+        result
+    }
+
+    /// Returns a copy of this string with ASCII letters uppercased, as with
+    /// `str::to_ascii_uppercase`. The result has the same length, so it is
+    /// stored inline whenever this string fits inline.
+    ///
+    /// Like `to_lowercase_inlinable`, this has its own name so that
+    /// `s.to_ascii_uppercase()` still returns a `String`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use inlinable_string::InlinableString;
+    ///
+    /// let s = InlinableString::from("select");
+    /// let upper = s.to_ascii_uppercase_inlinable();
+    /// assert_eq!(upper, "SELECT");
+    /// assert!(upper.is_inline());
+    /// ```
+    #[inline]
+    pub fn to_ascii_uppercase_inlinable(&self) -> InlinableString {
+        let mut result = self.demoted_clone();
+        result.make_ascii_uppercase();
+        result
+    }
 
+    /// Clones this string, storing the copy inline if it fits even when this
+    /// string is on the heap.
+    fn demoted_clone(&self) -> InlinableString {
         match *self {
-            InlinableString::Heap(ref mut s) => s.shrink_to_fit(),
-            _ => panic!("inlinable_string: internal error: this branch should be unreachable"),
-        };
+            InlinableString::Heap(ref s) if s.len() <= InlineString::CAPACITY => {
+                InlinableString::Inline(InlineString::from(&s[..]))
+            }
+            _ => self.clone(),
+        }
     }
 
+    /// Replaces all matches of `from` with `to`, as with `str::replace`. The
+    /// result's length is computed up front, so it is stored inline whenever
+    /// it fits.
+    ///
+    /// Like `to_lowercase_inlinable`, this has its own name so that
+    /// `s.replace(..)` still reaches `str::replace`, which accepts any
+    /// pattern and returns a `String`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use inlinable_string::InlinableString;
+    ///
+    /// let s = InlinableString::from("this is old");
+    /// let replaced = s.replace_inlinable("old", "new");
+    /// assert_eq!(replaced, "this is new");
+    /// assert!(replaced.is_inline());
+    /// ```
     #[inline]
-    fn push(&mut self, ch: char) {
+    pub fn replace_inlinable(&self, from: &str, to: &str) -> InlinableString {
+        self.replacen_inlinable(from, to, usize::MAX)
+    }
+
+    /// Replaces the first `count` matches of `pat` with `to`, as with
+    /// `str::replacen`. The result's length is computed up front, so it is
+    /// stored inline whenever it fits. See `replace_inlinable` for why this
+    /// is not called `replacen`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use inlinable_string::InlinableString;
+    ///
+    /// let s = InlinableString::from("foo foo 123 foo");
+    /// assert_eq!(s.replacen_inlinable("foo", "new", 2), "new new 123 foo");
+    /// ```
+    pub fn replacen_inlinable(&self, pat: &str, to: &str, count: usize) -> InlinableString {
+        let matches = self.match_indices(pat).take(count).count();
+        let len = self.len() - matches * pat.len() + matches * to.len();
+
+        let mut result = InlinableString::with_capacity(len);
+        let mut last_end = 0;
+        for (start, part) in self.match_indices(pat).take(count) {
+            result.push_str(&self[last_end..start]);
+            result.push_str(to);
+            last_end = start + part.len();
+        }
+        result.push_str(&self[last_end..]);
+        result
+    }
+
+    /// Returns this string repeated `n` times. The result's length is
+    /// computed up front, so it is stored inline whenever it fits.
+    ///
+    /// `str::repeat` always allocates a `String`; this method has its own
+    /// name so it does not shadow it.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the result's length would overflow `usize`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use inlinable_string::InlinableString;
+    ///
+    /// let s = InlinableString::from("ab");
+    /// let repeated = s.repeat_inlinable(3);
+    /// assert_eq!(repeated, "ababab");
+    /// assert!(repeated.is_inline());
+    /// ```
+    #[inline]
+    pub fn repeat_inlinable(&self, n: usize) -> InlinableString {
+        StringExt::repeat_inlinable(self, n)
+    }
+
+    /// Consumes and leaks this string, returning a mutable reference to its
+    /// contents, as with `String::leak`.
+    ///
+    /// An inline string has no heap buffer to leak, so its contents are first
+    /// copied into a new heap allocation, which is then leaked.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use inlinable_string::InlinableString;
+    ///
+    /// let s = InlinableString::from("config");
+    /// let leaked: &'static mut str = s.leak();
+    /// leaked.make_ascii_uppercase();
+    /// assert_eq!(leaked, "CONFIG");
+    /// ```
+    #[inline]
+    pub fn leak<'a>(self) -> &'a mut str {
+        String::from(self).leak()
+    }
+
+    /// Extracts a string slice containing the entire string.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use inlinable_string::InlinableString;
+    ///
+    /// let s = InlinableString::from("foo");
+    /// assert_eq!(s.as_str(), "foo");
+    /// ```
+    #[inline]
+    pub fn as_str(&self) -> &str {
+        self
+    }
+
+    /// Extracts a mutable string slice containing the entire string.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use inlinable_string::InlinableString;
+    ///
+    /// let mut s = InlinableString::from("foo");
+    /// s.as_mut_str().make_ascii_uppercase();
+    /// assert_eq!(s, "FOO");
+    /// ```
+    #[inline]
+    pub fn as_mut_str(&mut self) -> &mut str {
+        self
+    }
+
+    /// Returns a raw pointer to the start of the string's bytes, wherever
+    /// they are stored.
+    ///
+    /// The pointer is valid for reads of `self.len()` bytes for as long as
+    /// the string is neither moved nor modified. Note that moving an inline
+    /// string moves its bytes, while moving a heap-allocated one does not.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use inlinable_string::InlinableString;
+    ///
+    /// let s = InlinableString::from("hello");
+    /// let bytes = unsafe { std::slice::from_raw_parts(s.as_ptr(), s.len()) };
+    /// assert_eq!(bytes, b"hello");
+    /// ```
+    #[inline]
+    pub fn as_ptr(&self) -> *const u8 {
+        match *self {
+            InlinableString::Heap(ref s) => s.as_ptr(),
+            InlinableString::Inline(ref s) => s.as_ptr(),
+        }
+    }
+
+    /// Returns a raw mutable pointer to the start of the string's bytes,
+    /// wherever they are stored.
+    ///
+    /// The pointer is valid for reads and writes of `self.len()` bytes for as
+    /// long as the string is neither moved nor otherwise accessed. The caller
+    /// must ensure that the bytes remain valid UTF-8.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use inlinable_string::InlinableString;
+    ///
+    /// let mut s = InlinableString::from("hello");
+    /// unsafe { *s.as_mut_ptr() = b'H' };
+    /// assert_eq!(s, "Hello");
+    /// ```
+    #[inline]
+    pub fn as_mut_ptr(&mut self) -> *mut u8 {
+        match *self {
+            InlinableString::Heap(ref mut s) => s.as_mut_str().as_mut_ptr(),
+            InlinableString::Inline(ref mut s) => s.as_mut_ptr(),
+        }
+    }
+
+    /// Returns a guard for writing into the string's spare capacity, to be
+    /// appended with `SpareCapacity::commit`.
+    ///
+    /// The guard covers the capacity left in whichever storage the string
+    /// currently uses; call `reserve` first to make room for more. For a
+    /// heap-allocated string, it is the string's own uninitialized spare
+    /// capacity, as with `Vec::spare_capacity_mut`. For an inline string, it
+    /// is a scratch buffer that `commit` copies into the string.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use inlinable_string::{InlinableString, StringExt};
+    ///
+    /// let mut s = InlinableString::from("n=");
+    /// s.reserve(64);
+    /// let mut spare = s.spare_capacity_mut();
+    /// assert!(spare.len() >= 64);
+    /// spare[0].write(b'4');
+    /// spare[1].write(b'2');
+    /// // Safe because the first two bytes were just written.
+    /// unsafe { spare.commit(2).unwrap() };
+    /// assert_eq!(s, "n=42");
+    /// ```
+    pub fn spare_capacity_mut(&mut self) -> SpareCapacity<'_> {
+        match *self {
+            InlinableString::Heap(ref mut s) => SpareCapacity::heap(s),
+            InlinableString::Inline(ref mut s) => s.spare_capacity_mut(),
+        }
+    }
+
+    /// Replaces the contents of this string with `string`, reusing the
+    /// existing storage where possible.
+    ///
+    /// A heap-allocated string keeps its buffer whenever the buffer is large
+    /// enough for `string`, so repeatedly overwriting the same string does not
+    /// reallocate. It is only demoted to inline storage when `string` fits
+    /// inline but not in the current buffer.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use inlinable_string::{InlinableString, StringExt};
+    ///
+    /// let mut s = InlinableString::with_capacity(64);
+    /// s.assign("first");
+    /// assert_eq!(s, "first");
+    /// assert!(s.capacity() >= 64);
+    ///
+    /// let mut t = InlinableString::new();
+    /// t.assign("a string that is long enough to need heap storage");
+    /// assert!(t.is_heap());
+    /// ```
+    pub fn assign(&mut self, string: &str) {
+        match *self {
+            InlinableString::Heap(ref mut s) if s.capacity() >= string.len() => {
+                s.clear();
+                s.push_str(string);
+                return;
+            }
+            InlinableString::Inline(ref mut s) if string.len() <= InlineString::CAPACITY => {
+                s.clear();
+                s.push_str(string)
+                    .expect("inlinable_string: internal error: `string` fits inline");
+                return;
+            }
+            _ => {}
+        }
+
+        let new = if string.len() <= InlineString::CAPACITY {
+            InlinableString::Inline(InlineString::from(string))
+        } else {
+            let mut buffer = heap_buffer(string.len());
+            buffer.push_str(string);
+            InlinableString::Heap(buffer)
+        };
+        let old = mem::replace(self, new);
+        recycle_heap_buffer(old);
+    }
+
+    /// Appends the `Display` representation of `value` to the string,
+    /// formatting directly into its storage rather than through a temporary
+    /// `String`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `value`'s `Display` implementation returns an error, as
+    /// `ToString::to_string` does.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use inlinable_string::InlinableString;
+    ///
+    /// let mut s = InlinableString::from("x = ");
+    /// s.push_display(&42);
+    /// s.push_display(", ");
+    /// s.push_display(&1.5);
+    /// assert_eq!(s, "x = 42, 1.5");
+    /// assert!(s.is_inline());
+    /// ```
+    pub fn push_display<T: fmt::Display + ?Sized>(&mut self, value: &T) {
+        fmt::Write::write_fmt(self, format_args!("{}", value))
+            .expect("a Display implementation returned an error unexpectedly");
+    }
+
+    /// Retains only the characters for which `f` returns true, removing the
+    /// rest in place.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use inlinable_string::InlinableString;
+    ///
+    /// let mut s = InlinableString::from("h-é-l-l-o");
+    /// s.retain(|c| c != '-');
+    /// assert_eq!(s, "héllo");
+    /// ```
+    #[inline]
+    pub fn retain<F: FnMut(char) -> bool>(&mut self, f: F) {
+        match *self {
+            InlinableString::Heap(ref mut s) => s.retain(f),
+            InlinableString::Inline(ref mut s) => s.retain(f),
+        }
+    }
+
+    /// Removes every non-overlapping occurrence of `pat` from the string in
+    /// place, without allocating. A heap-allocated string keeps its buffer.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use inlinable_string::InlinableString;
+    ///
+    /// let mut s = InlinableString::from("a string with a tail, a tail, a tail");
+    /// s.remove_matches(", a tail");
+    /// assert_eq!(s, "a string with a tail");
+    /// ```
+    pub fn remove_matches(&mut self, pat: &str) {
+        match *self {
+            InlinableString::Heap(ref mut s) => unsafe {
+                let bytes = s.as_mut_vec();
+                let new_len = inline_string::remove_matches_in(bytes, pat);
+                bytes.truncate(new_len);
+            },
+            InlinableString::Inline(ref mut s) => s.remove_matches(pat),
+        }
+    }
+
+    /// Retains only the ASCII bytes for which `predicate` returns true,
+    /// removing the rest in place. Non-ASCII characters are always kept.
+    ///
+    /// See `InlineString::retain_bytes` for more.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use inlinable_string::InlinableString;
+    ///
+    /// let mut s = InlinableString::from("a1b2c3é4");
+    /// s.retain_bytes(|b: u8| !b.is_ascii_digit());
+    /// assert_eq!(s, "abcé");
+    /// ```
+    #[inline]
+    pub fn retain_bytes<P: AsciiPredicate>(&mut self, mut predicate: P) {
+        struct TruncateOnDrop<'a> {
+            bytes: &'a mut Vec<u8>,
+            write: usize,
+        }
+
+        impl<'a> Drop for TruncateOnDrop<'a> {
+            fn drop(&mut self) {
+                self.bytes.truncate(self.write);
+            }
+        }
+
+        match *self {
+            // The predicate only sees ASCII bytes, which always start a
+            // character, so the bytes kept so far stay valid UTF-8 even if it
+            // panics part way through.
+            InlinableString::Heap(ref mut s) => {
+                let mut kept = TruncateOnDrop {
+                    bytes: unsafe { s.as_mut_vec() },
+                    write: 0,
+                };
+                let TruncateOnDrop {
+                    ref mut bytes,
+                    ref mut write,
+                } = kept;
+                inline_string::retain_ascii_in(bytes, write, &mut predicate);
+            }
+            InlinableString::Inline(ref mut s) => s.retain_bytes(predicate),
+        }
+    }
+
+    /// Rotates the string in place so that its first `n` characters move to
+    /// the end. `n` wraps around the number of characters in the string.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use inlinable_string::InlinableString;
+    ///
+    /// let mut s = InlinableString::from("héllo");
+    /// s.rotate_left_chars(2);
+    /// assert_eq!(s, "llohé");
+    /// ```
+    #[inline]
+    pub fn rotate_left_chars(&mut self, n: usize) {
+        match *self {
+            InlinableString::Heap(ref mut s) => {
+                let mid = inline_string::rotate_left_mid(s, n);
+                // Rotating around a character boundary keeps the contents
+                // valid UTF-8.
+                unsafe { s.as_mut_vec().rotate_left(mid) }
+            }
+            InlinableString::Inline(ref mut s) => s.rotate_left_chars(n),
+        }
+    }
+
+    /// Rotates the string in place so that its last `n` characters move to
+    /// the front. `n` wraps around the number of characters in the string.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use inlinable_string::InlinableString;
+    ///
+    /// let mut s = InlinableString::from("héllo");
+    /// s.rotate_right_chars(4);
+    /// assert_eq!(s, "élloh");
+    /// ```
+    #[inline]
+    pub fn rotate_right_chars(&mut self, n: usize) {
+        match *self {
+            InlinableString::Heap(ref mut s) => {
+                let mid = inline_string::rotate_right_mid(s, n);
+                unsafe { s.as_mut_vec().rotate_left(mid) }
+            }
+            InlinableString::Inline(ref mut s) => s.rotate_right_chars(n),
+        }
+    }
+}
+
+impl<'a> StringExt<'a> for InlinableString {
+    #[inline]
+    fn new() -> Self {
+        InlinableString::new()
+    }
+
+    #[inline]
+    fn with_capacity(capacity: usize) -> Self {
+        if capacity <= InlineString::CAPACITY {
+            InlinableString::Inline(InlineString::new())
+        } else {
+            InlinableString::Heap(heap_buffer(capacity))
+        }
+    }
+
+    #[inline]
+    fn from_utf8(vec: Vec<u8>) -> Result<Self, FromUtf8Error> {
+        if vec.len() <= InlineString::CAPACITY {
+            if let Ok(s) = core::str::from_utf8(&vec) {
+                return Ok(InlinableString::Inline(InlineString::from(s)));
+            }
+        }
+        String::from_utf8(vec).map(InlinableString::Heap)
+    }
+
+    #[inline]
+    fn from_utf16(v: &[u16]) -> Result<Self, FromUtf16Error> {
+        match decode_utf16_inline(v, false) {
+            Some(s) => Ok(InlinableString::Inline(s)),
+            None => String::from_utf16(v).map(InlinableString::Heap),
+        }
+    }
+
+    #[inline]
+    fn from_utf16_lossy(v: &[u16]) -> Self {
+        match decode_utf16_inline(v, true) {
+            Some(s) => InlinableString::Inline(s),
+            None => InlinableString::Heap(String::from_utf16_lossy(v)),
+        }
+    }
+
+    #[inline]
+    unsafe fn from_raw_parts(buf: *mut u8, length: usize, capacity: usize) -> Self {
+        InlinableString::Heap(String::from_raw_parts(buf, length, capacity))
+    }
+
+    #[inline]
+    unsafe fn from_utf8_unchecked(bytes: Vec<u8>) -> Self {
+        InlinableString::Heap(String::from_utf8_unchecked(bytes))
+    }
+
+    #[inline]
+    fn into_bytes(self) -> Vec<u8> {
+        match self {
+            InlinableString::Heap(s) => s.into_bytes(),
+            InlinableString::Inline(s) => Vec::from(&s[..]),
+        }
+    }
+
+    #[inline]
+    fn into_boxed_str(self) -> Box<str> {
+        Box::from(self)
+    }
+
+    #[inline]
+    fn push_str(&mut self, string: &str) {
+        let promoted = match *self {
+            InlinableString::Inline(ref mut s) => {
+                if s.push_str(string).is_ok() {
+                    return;
+                }
+                let mut promoted = heap_buffer(string.len() + s.len());
+                promoted.push_str(&*s);
+                promoted.push_str(string);
+                promoted
+            }
+            InlinableString::Heap(ref mut s) => {
+                s.push_str(string);
+                return;
+            }
+        };
+        *self = InlinableString::Heap(promoted);
+    }
+
+    #[inline]
+    fn capacity(&self) -> usize {
+        match *self {
+            InlinableString::Heap(ref s) => s.capacity(),
+            InlinableString::Inline(_) => InlineString::CAPACITY,
+        }
+    }
+
+    #[inline]
+    fn reserve(&mut self, additional: usize) {
+        let promoted = match *self {
+            InlinableString::Inline(ref s) => {
+                let new_capacity = s.len() + additional;
+                if new_capacity <= InlineString::CAPACITY {
+                    return;
+                }
+                let mut promoted = heap_buffer(new_capacity);
+                promoted.push_str(s);
+                promoted
+            }
+            InlinableString::Heap(ref mut s) => {
+                s.reserve(additional);
+                return;
+            }
+        };
+        *self = InlinableString::Heap(promoted);
+    }
+
+    #[inline]
+    fn reserve_exact(&mut self, additional: usize) {
+        let promoted = match *self {
+            InlinableString::Inline(ref s) => {
+                let new_capacity = s.len() + additional;
+                if new_capacity <= InlineString::CAPACITY {
+                    return;
+                }
+                let mut promoted = heap_buffer(new_capacity);
+                promoted.push_str(s);
+                promoted
+            }
+            InlinableString::Heap(ref mut s) => {
+                s.reserve_exact(additional);
+                return;
+            }
+        };
+        *self = InlinableString::Heap(promoted);
+    }
+
+    #[inline]
+    fn try_reserve(&mut self, additional: usize) -> Result<(), TryReserveError> {
+        let promoted = match *self {
+            InlinableString::Inline(ref s) => {
+                let new_capacity = s.len().saturating_add(additional);
+                if new_capacity <= InlineString::CAPACITY {
+                    return Ok(());
+                }
+                let mut promoted = try_heap_buffer(new_capacity)?;
+                promoted.push_str(s);
+                promoted
+            }
+            InlinableString::Heap(ref mut s) => return s.try_reserve(additional),
+        };
+        *self = InlinableString::Heap(promoted);
+        Ok(())
+    }
+
+    #[inline]
+    fn try_reserve_exact(&mut self, additional: usize) -> Result<(), TryReserveError> {
+        let promoted = match *self {
+            InlinableString::Inline(ref s) => {
+                let new_capacity = s.len().saturating_add(additional);
+                if new_capacity <= InlineString::CAPACITY {
+                    return Ok(());
+                }
+                let mut promoted = try_heap_buffer(new_capacity)?;
+                promoted.push_str(s);
+                promoted
+            }
+            InlinableString::Heap(ref mut s) => return s.try_reserve_exact(additional),
+        };
+        *self = InlinableString::Heap(promoted);
+        Ok(())
+    }
+
+    #[inline]
+    fn shrink_to_fit(&mut self) {
+        if self.make_inline() {
+            return;
+        }
+
+        match *self {
+            InlinableString::Heap(ref mut s) => s.shrink_to_fit(),
+            _ => panic!("inlinable_string: internal error: this branch should be unreachable"),
+        };
+    }
+
+    #[inline]
+    fn shrink_to(&mut self, min_capacity: usize) {
+        if self.len().max(min_capacity) <= InlineString::CAPACITY {
+            self.shrink_to_fit();
+            return;
+        }
+
+        if let InlinableString::Heap(ref mut s) = *self {
+            s.shrink_to(min_capacity);
+        }
+    }
+
+    #[inline]
+    fn push(&mut self, ch: char) {
         let promoted = match *self {
             InlinableString::Inline(ref mut s) => {
                 if s.push(ch).is_ok() {
                     return;
                 }
 
-                let mut promoted = String::with_capacity(s.len() + 1);
+                let mut promoted = heap_buffer(s.len() + 1);
                 promoted.push_str(&*s);
                 promoted.push(ch);
                 promoted
@@ -574,7 +1914,7 @@ impl<'a> StringExt<'a> for InlinableString {
             }
         };
 
-        mem::swap(self, &mut InlinableString::Heap(promoted));
+        *self = InlinableString::Heap(promoted);
     }
 
     #[inline]
@@ -621,7 +1961,7 @@ impl<'a> StringExt<'a> for InlinableString {
                     return;
                 }
 
-                let mut promoted = String::with_capacity(s.len() + 1);
+                let mut promoted = heap_buffer(s.len() + 1);
                 promoted.push_str(&s[..idx]);
                 promoted.push(ch);
                 promoted.push_str(&s[idx..]);
@@ -629,7 +1969,7 @@ impl<'a> StringExt<'a> for InlinableString {
             }
         };
 
-        mem::swap(self, &mut InlinableString::Heap(promoted));
+        *self = InlinableString::Heap(promoted);
     }
 
     #[inline]
@@ -644,7 +1984,7 @@ impl<'a> StringExt<'a> for InlinableString {
                     return;
                 }
 
-                let mut promoted = String::with_capacity(s.len() + string.len());
+                let mut promoted = heap_buffer(s.len() + string.len());
                 promoted.push_str(&s[..idx]);
                 promoted.push_str(string);
                 promoted.push_str(&s[idx..]);
@@ -652,7 +1992,7 @@ impl<'a> StringExt<'a> for InlinableString {
             }
         };
 
-        mem::swap(self, &mut InlinableString::Heap(promoted));
+        *self = InlinableString::Heap(promoted);
     }
 
     #[inline]
@@ -670,13 +2010,59 @@ impl<'a> StringExt<'a> for InlinableString {
             InlinableString::Inline(ref s) => s.len(),
         }
     }
+
+    #[inline]
+    fn drain<R: ops::RangeBounds<usize>>(&mut self, range: R) -> Drain<'_> {
+        match *self {
+            InlinableString::Heap(ref mut s) => Drain::heap(s.drain(range)),
+            InlinableString::Inline(ref mut s) => s.drain(range),
+        }
+    }
+
+    #[inline]
+    fn extend_from_within<R: ops::RangeBounds<usize>>(&mut self, src: R) {
+        let promoted = match *self {
+            InlinableString::Heap(ref mut s) => {
+                StringExt::extend_from_within(s, src);
+                return;
+            }
+            InlinableString::Inline(ref mut s) => {
+                let src = drain::resolve_range(s, src);
+                let copy = InlineString::from(&s[src]);
+                if s.push_str(&copy).is_ok() {
+                    return;
+                }
+                let mut promoted = heap_buffer(s.len() + copy.len());
+                promoted.push_str(s);
+                promoted.push_str(&copy);
+                promoted
+            }
+        };
+        *self = InlinableString::Heap(promoted);
+    }
+
+    #[inline]
+    fn nth_char(&self, n: usize) -> Option<char> {
+        match *self {
+            InlinableString::Heap(ref s) => s.chars().nth(n),
+            // Checking a short inline string for non-ASCII bytes is cheap, and
+            // lets ASCII strings skip decoding.
+            InlinableString::Inline(ref s) if s.is_ascii() => {
+                s.as_bytes().get(n).map(|&b| char::from(b))
+            }
+            InlinableString::Inline(ref s) => s.chars().nth(n),
+        }
+    }
 }
 
 #[cfg(test)]
 mod tests {
+    use alloc::borrow::Cow;
+    use alloc::boxed::Box;
     use alloc::string::{String, ToString};
+    use alloc::vec::Vec;
     use core::iter::FromIterator;
-    use super::{InlinableString, StringExt, INLINE_STRING_CAPACITY};
+    use super::{InlinableString, InlineString, StringExt};
     use core::cmp::Ordering;
     use core::str::FromStr;
 
@@ -696,7 +2082,7 @@ mod tests {
         assert_eq!(s, "small");
 
         let long_str = "this is a really long string that is much larger than
-                        INLINE_STRING_CAPACITY and so cannot be stored inline.";
+                        InlineString::CAPACITY and so cannot be stored inline.";
         s.push_str(long_str);
         assert_eq!(s, String::from("small") + long_str);
     }
@@ -709,7 +2095,7 @@ mod tests {
         assert_eq!(s, "small");
 
         let long_str = "this is a really long string that is much larger than
-                        INLINE_STRING_CAPACITY and so cannot be stored inline.";
+                        InlineString::CAPACITY and so cannot be stored inline.";
         write!(&mut s, "{}", long_str).expect("!write");
         assert_eq!(s, String::from("small") + long_str);
     }
@@ -718,14 +2104,14 @@ mod tests {
     fn test_push() {
         let mut s = InlinableString::new();
 
-        for _ in 0..INLINE_STRING_CAPACITY {
+        for _ in 0..InlineString::CAPACITY {
             s.push('a');
         }
         s.push('a');
 
         assert_eq!(
             s,
-            String::from_iter((0..INLINE_STRING_CAPACITY + 1).map(|_| 'a'))
+            String::from_iter((0..InlineString::CAPACITY + 1).map(|_| 'a'))
         );
     }
 
@@ -733,14 +2119,14 @@ mod tests {
     fn test_insert() {
         let mut s = InlinableString::new();
 
-        for _ in 0..INLINE_STRING_CAPACITY {
+        for _ in 0..InlineString::CAPACITY {
             s.insert(0, 'a');
         }
         s.insert(0, 'a');
 
         assert_eq!(
             s,
-            String::from_iter((0..INLINE_STRING_CAPACITY + 1).map(|_| 'a'))
+            String::from_iter((0..InlineString::CAPACITY + 1).map(|_| 'a'))
         );
     }
 
@@ -748,14 +2134,14 @@ mod tests {
     fn test_insert_str() {
         let mut s = InlinableString::new();
 
-        for _ in 0..(INLINE_STRING_CAPACITY / 3) {
+        for _ in 0..(InlineString::CAPACITY / 3) {
             s.insert_str(0, "foo");
         }
         s.insert_str(0, "foo");
 
         assert_eq!(
             s,
-            String::from_iter((0..(INLINE_STRING_CAPACITY / 3) + 1).map(|_| "foo"))
+            String::from_iter((0..(InlineString::CAPACITY / 3) + 1).map(|_| "foo"))
         );
     }
 
@@ -776,14 +2162,41 @@ mod tests {
     #[test]
     fn test_from_utf8() {
         let s = <InlinableString as StringExt>::from_utf8(vec![104, 101, 108, 108, 111]);
-        assert_eq!(s.unwrap(), "hello");
+        let s = s.unwrap();
+        assert_eq!(s, "hello");
+        assert!(s.is_inline());
+
+        let long = "a string that is too long to be stored inline";
+        let s = <InlinableString as StringExt>::from_utf8(long.as_bytes().to_vec()).unwrap();
+        assert_eq!(s, long);
+        assert!(s.is_heap());
+
+        let err = <InlinableString as StringExt>::from_utf8(vec![b'a', 0xc3, 0x28]).unwrap_err();
+        assert_eq!(err.into_bytes(), [b'a', 0xc3, 0x28]);
     }
 
     #[test]
     fn test_from_utf16() {
         let v = &mut [0xD834, 0xDD1E, 0x006d, 0x0075, 0x0073, 0x0069, 0x0063];
-        let s = <InlinableString as StringExt>::from_utf16(v);
-        assert_eq!(s.unwrap(), "𝄞music");
+        let s = <InlinableString as StringExt>::from_utf16(v).unwrap();
+        assert_eq!(s, "𝄞music");
+        assert!(s.is_inline());
+
+        let long: Vec<u16> = "a string that is too long to be stored inline"
+            .encode_utf16()
+            .collect();
+        let s = <InlinableString as StringExt>::from_utf16(&long).unwrap();
+        assert_eq!(s, "a string that is too long to be stored inline");
+        assert!(s.is_heap());
+
+        // Three-byte characters from two-byte code units overflow inline
+        // storage even though there are few enough code units.
+        let wide: Vec<u16> = "€".repeat(InlineString::CAPACITY / 2).encode_utf16().collect();
+        let s = <InlinableString as StringExt>::from_utf16(&wide).unwrap();
+        assert_eq!(s, &"€".repeat(InlineString::CAPACITY / 2)[..]);
+        assert!(s.is_heap());
+
+        assert!(<InlinableString as StringExt>::from_utf16(&[0x0061, 0xD800]).is_err());
     }
 
     #[test]
@@ -791,6 +2204,256 @@ mod tests {
         let input = b"Hello \xF0\x90\x80World";
         let output = <InlinableString as StringExt>::from_utf8_lossy(input);
         assert_eq!(output, "Hello \u{FFFD}World");
+
+        let s = <InlinableString as StringExt>::from_utf16_lossy(&[0x0061, 0xD800, 0x0062]);
+        assert_eq!(s, "a\u{FFFD}b");
+        assert!(s.is_inline());
+    }
+
+    #[test]
+    fn test_extend_str_reserves_bytes() {
+        // Many empty slices must not be mistaken for many bytes.
+        let mut s = InlinableString::from("a");
+        s.extend([""; 100].iter().copied());
+        assert!(s.is_inline());
+
+        let parts: Vec<String> = (0..40).map(|i| i.to_string()).collect();
+        let mut s = InlinableString::new();
+        s.extend(parts.iter().map(|p| &p[..]));
+        assert_eq!(s, &parts.concat()[..]);
+        assert!(s.is_heap());
+    }
+
+    #[test]
+    fn test_checked_slicing() {
+        // `get` and `get_mut` come from `str` through `Deref`/`DerefMut`.
+        let mut s = InlinableString::from("héllo");
+        assert_eq!(s.get(0..1), Some("h"));
+        assert_eq!(s.get(0..2), None);
+        assert_eq!(s.get(4..10), None);
+        s.get_mut(3..).unwrap().make_ascii_uppercase();
+        assert_eq!(s, "héLLO");
+
+        let mut heap = InlinableString::from("a string that is too long to be stored inline");
+        assert_eq!(heap.get(2..8), Some("string"));
+        assert!(heap.get_mut(100..).is_none());
+
+        let mut inline = crate::InlineString::from("é");
+        assert_eq!(inline.get(..1), None);
+        assert_eq!(inline.get_mut(..).map(|s| &*s), Some("é"));
+    }
+
+    #[test]
+    fn test_eq_across_variants() {
+        let inline = InlinableString::from("same");
+        let heap = InlinableString::Heap(String::from("same"));
+        assert_eq!(inline, heap);
+        assert_eq!(heap, inline);
+        assert_eq!(inline, InlinableString::from("same"));
+        assert_ne!(inline, InlinableString::from("some"));
+    }
+
+    #[test]
+    fn test_force_heap() {
+        let mut s = InlinableString::from("abc");
+        s.force_heap(0).push('d');
+        assert!(s.is_heap());
+        assert_eq!(s, "abcd");
+
+        let ptr = s.as_ptr();
+        let heap = s.force_heap(0);
+        assert_eq!(heap.as_ptr(), ptr);
+        assert!(s.force_heap(100).capacity() >= 104);
+    }
+
+    #[test]
+    fn test_make_inline() {
+        let mut s = InlinableString::from("inline");
+        assert!(s.make_inline());
+        assert_eq!(s, "inline");
+
+        let mut s = InlinableString::with_capacity(100);
+        s.push_str(&"a".repeat(InlineString::CAPACITY + 1));
+        assert!(!s.make_inline());
+        assert!(s.capacity() >= 100);
+
+        s.pop();
+        assert!(s.make_inline());
+        assert!(s.is_inline());
+        assert_eq!(s.len(), InlineString::CAPACITY);
+    }
+
+    #[test]
+    fn test_display_flags_across_variants() {
+        let inline = InlinableString::from("añb");
+        let heap = InlinableString::Heap(String::from("añb"));
+        for s in &[inline, heap] {
+            assert_eq!(format!("[{:>5}]", s), "[  añb]");
+            assert_eq!(format!("[{:-^7.2}]", s), "[--añ---]");
+        }
+    }
+
+    #[test]
+    fn test_as_str() {
+        let mut s = InlinableString::from("a string long enough to live on the heap");
+        assert_eq!(s.as_str(), "a string long enough to live on the heap");
+        s.as_mut_str().make_ascii_uppercase();
+        assert_eq!(s.as_str(), "A STRING LONG ENOUGH TO LIVE ON THE HEAP");
+    }
+
+    #[test]
+    fn test_truncate_chars() {
+        let mut s = InlinableString::from("ünïcödé text that lives on the heap");
+        s.truncate_chars(7);
+        assert_eq!(s, "ünïcödé");
+        s.truncate_chars(100);
+        assert_eq!(s, "ünïcödé");
+    }
+
+    #[test]
+    fn test_remaining_capacity() {
+        let mut s = InlinableString::new();
+        assert_eq!(s.remaining_capacity(), InlineString::CAPACITY);
+        s.push_str(&"a".repeat(InlineString::CAPACITY));
+        assert_eq!(s.remaining_capacity(), 0);
+        assert!(s.is_inline());
+
+        let mut s = InlinableString::with_capacity(100);
+        s.push_str("abc");
+        assert_eq!(s.remaining_capacity(), s.capacity() - 3);
+        assert!(s.remaining_capacity() >= 97);
+    }
+
+    #[test]
+    fn test_from_fmt() {
+        let s = InlinableString::from_fmt(format_args!("plain"));
+        assert_eq!(s, "plain");
+        assert!(s.is_inline());
+
+        let s = InlinableString::from_fmt(format_args!("{:>width$}", "x", width = 40));
+        assert_eq!(s.len(), 40);
+        assert!(s.is_heap());
+        assert!(s.ends_with('x'));
+    }
+
+    #[test]
+    fn test_push_display() {
+        let mut s = InlinableString::new();
+        s.push_display(&'a');
+        s.push_display(&-7i32);
+        s.push_display(&InlinableString::from("b"));
+        assert_eq!(s, "a-7b");
+        assert!(s.is_inline());
+
+        s.push_display(&"c".repeat(InlineString::CAPACITY));
+        assert!(s.is_heap());
+        assert_eq!(s.len(), 4 + InlineString::CAPACITY);
+    }
+
+    #[test]
+    fn test_split_off_shrink() {
+        let mut s = InlinableString::from("a string that is too long to be stored inline");
+        let tail = s.split_off_shrink(8);
+        assert_eq!(s, "a string");
+        assert!(s.is_inline());
+        assert_eq!(tail, " that is too long to be stored inline");
+        assert!(tail.is_heap());
+
+        let tail = s.split_off_shrink(2);
+        assert_eq!(s, "a ");
+        assert_eq!(tail, "string");
+        assert!(tail.is_inline());
+
+        let mut long = InlinableString::from("a string that is too long to be stored inline");
+        let tail = long.split_off_shrink(long.len() - 6);
+        assert!(long.is_heap());
+        assert_eq!(tail, "inline");
+
+        let tail = long.split_off_shrink(long.len());
+        assert_eq!(tail, "");
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_split_off_shrink_not_char_boundary() {
+        let mut s = InlinableString::from("é");
+        s.split_off_shrink(1);
+    }
+
+    #[test]
+    fn test_remove_matches() {
+        let mut s = InlinableString::from("one and two and three and four");
+        let ptr = s.as_ptr();
+        s.remove_matches(" and ");
+        assert_eq!(s, "onetwothreefour");
+        assert_eq!(s.as_ptr(), ptr);
+
+        let mut s = InlinableString::from("x-y-z");
+        s.remove_matches("-");
+        assert_eq!(s, "xyz");
+        assert!(s.is_inline());
+    }
+
+    #[test]
+    fn test_cross_type_ord() {
+        let s = InlinableString::from("banana");
+        let banana = String::from("banana");
+        let cherry = String::from("cherry");
+        let inline = crate::InlineString::from("banana");
+        assert!(s < *"cherry");
+        assert!(s > "apple");
+        assert!(s >= banana);
+        assert!(s <= inline);
+        assert!(Cow::Borrowed("apple") < s);
+        assert!("apple" < s);
+        assert!(cherry > s);
+        assert_eq!("banana".partial_cmp(&s), Some(Ordering::Equal));
+    }
+
+    #[test]
+    fn test_clone_from() {
+        let long = InlinableString::from("a string that is too long to be stored inline");
+        let mut dst = InlinableString::with_capacity(100);
+        let ptr = dst.as_ptr();
+
+        dst.clone_from(&long);
+        assert_eq!(dst, long);
+        assert_eq!(dst.as_ptr(), ptr);
+
+        dst.clone_from(&InlinableString::from("short"));
+        assert_eq!(dst, "short");
+        assert_eq!(dst.as_ptr(), ptr);
+
+        let mut inline = InlinableString::from("inline");
+        inline.clone_from(&long);
+        assert_eq!(inline, long);
+        assert_eq!(long.clone(), long);
+    }
+
+    #[test]
+    fn test_const_new() {
+        const EMPTY: InlinableString = InlinableString::new();
+        assert_eq!(EMPTY, "");
+        assert!(EMPTY.is_inline());
+        assert_eq!(InlinableString::default(), EMPTY);
+    }
+
+    #[test]
+    fn test_from_utf8_lossy_owned() {
+        let s = InlinableString::from_utf8_lossy_owned(b"\xffab\xc3");
+        assert_eq!(s, "\u{FFFD}ab\u{FFFD}");
+        assert!(s.is_inline());
+
+        // Each invalid byte becomes a three-byte replacement character.
+        let invalid = [0xff; InlineString::CAPACITY / 3 + 1];
+        let s = InlinableString::from_utf8_lossy_owned(&invalid);
+        assert_eq!(s.chars().count(), invalid.len());
+        assert!(s.chars().all(|c| c == '\u{FFFD}'));
+        assert!(s.is_heap());
+
+        let long = "a string that is too long to be stored inline";
+        let s = InlinableString::from_utf8_lossy_owned(long.as_bytes());
+        assert_eq!(s, long);
     }
 
     #[test]
@@ -825,7 +2488,7 @@ mod tests {
         let mut s = <InlinableString as StringExt>::with_capacity(100);
         StringExt::push_str(&mut s, "foo");
         StringExt::shrink_to_fit(&mut s);
-        assert_eq!(InlinableString::capacity(&s), INLINE_STRING_CAPACITY);
+        assert_eq!(InlinableString::capacity(&s), InlineString::CAPACITY);
     }
 
     #[test]
@@ -871,9 +2534,473 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_expand_tabs() {
+        let mut s = InlinableString::from("\tä\t");
+        s.expand_tabs(8);
+        assert_eq!(s, "        ä       ");
+        assert!(matches!(s, InlinableString::Inline(_)));
+
+        s.expand_tabs(8);
+        assert_eq!(s, "        ä       ");
+
+        let mut s = InlinableString::from("\t\t\t\t");
+        s.expand_tabs(8);
+        assert_eq!(s.len(), 32);
+        assert!(matches!(s, InlinableString::Heap(_)));
+    }
+
+    #[test]
+    fn test_extend_from_slices() {
+        let mut s = InlinableString::from("abc");
+        s.extend_from_slices(&["def", "ghi"]);
+        assert_eq!(s, "abcdefghi");
+        assert!(matches!(s, InlinableString::Inline(_)));
+
+        let long = "a".repeat(InlineString::CAPACITY);
+        s.extend_from_slices(&[&long, "xyz"]);
+        assert_eq!(s.len(), 9 + InlineString::CAPACITY + 3);
+        assert!(s.ends_with("axyz"));
+        assert!(matches!(s, InlinableString::Heap(_)));
+    }
+
+    #[test]
+    fn test_nth_char() {
+        let ascii = InlinableString::from("abc");
+        assert_eq!(ascii.nth_char(2), Some('c'));
+        assert_eq!(ascii.nth_char(3), None);
+
+        let unicode = InlinableString::from("añb");
+        assert_eq!(unicode.nth_char(2), Some('b'));
+        assert_eq!(unicode.nth_char(3), None);
+
+        let heap = InlinableString::from("ñ and a string too long to be stored inline");
+        assert_eq!(heap.nth_char(0), Some('ñ'));
+        assert_eq!(heap.nth_char(2), Some('a'));
+        assert_eq!(heap.char_at(1), None);
+    }
+
+    #[test]
+    fn test_retain() {
+        let mut s = InlinableString::from("1a2€3 and a long tail that lives on the heap 4");
+        assert!(matches!(s, InlinableString::Heap(_)));
+        s.retain_bytes(|b: u8| !b.is_ascii_digit());
+        assert_eq!(s, "a€ and a long tail that lives on the heap ");
+        s.retain(|c| c != ' ');
+        assert_eq!(s, "a€andalongtailthatlivesontheheap");
+    }
+
+    #[cfg(not(feature = "no_std"))]
+    #[test]
+    fn test_retain_bytes_panic_on_heap() {
+        let mut s = InlinableString::from("ab€€€€€€€€cd and a tail that lives on the heap");
+        assert!(s.is_heap());
+        let result = std::panic::catch_unwind(core::panic::AssertUnwindSafe(|| {
+            s.retain_bytes(|b: u8| if b == b'c' { panic!() } else { b != b'a' });
+        }));
+        assert!(result.is_err());
+        assert_eq!(s, "b€€€€€€€€");
+    }
+
+    #[test]
+    fn test_is_inline() {
+        let mut s = InlinableString::from("a".repeat(InlineString::CAPACITY).as_str());
+        assert!(s.is_inline() && !s.is_heap());
+        s.push('a');
+        assert!(s.is_heap() && !s.is_inline());
+        s.pop();
+        s.shrink_to_fit();
+        assert!(s.is_inline());
+    }
+
+    #[test]
+    fn test_assign() {
+        let long = "a string that is long enough to need heap storage";
+
+        let mut s = InlinableString::new();
+        s.assign("short");
+        assert_eq!(s, "short");
+        assert!(s.is_inline());
+
+        s.assign(long);
+        assert_eq!(s, long);
+        assert!(s.is_heap());
+        let ptr = s.as_ptr();
+
+        // Reuses the heap buffer, even for contents that would fit inline.
+        s.assign("tiny");
+        assert_eq!(s, "tiny");
+        assert_eq!(s.as_ptr(), ptr);
+        s.assign(long);
+        assert_eq!(s.as_ptr(), ptr);
+
+        // A heap buffer too small for the new contents is demoted instead.
+        let mut s = InlinableString::Heap(String::new());
+        s.assign("tiny");
+        assert_eq!(s, "tiny");
+        assert!(s.is_inline());
+    }
+
+    #[test]
+    fn test_try_into_inline() {
+        let inline = InlinableString::from("inline");
+        assert_eq!(inline.try_into_inline().unwrap(), "inline");
+
+        let heap = InlinableString::Heap(String::from("demotable"));
+        assert_eq!(heap.try_into_inline().unwrap(), "demotable");
+
+        let long = "a".repeat(InlineString::CAPACITY + 1);
+        let heap = InlinableString::from(&long[..]);
+        let heap = heap.try_into_inline().unwrap_err();
+        assert_eq!(heap, &long[..]);
+    }
+
+    #[test]
+    fn test_try_reserve() {
+        let mut s = InlinableString::from("small");
+        assert!(s.try_reserve(InlineString::CAPACITY - 5).is_ok());
+        assert!(s.is_inline());
+        assert!(s.try_reserve(usize::MAX).is_err());
+        assert!(s.is_inline());
+        assert_eq!(s, "small");
+
+        assert!(s.try_reserve_exact(100).is_ok());
+        assert!(s.is_heap());
+        assert!(s.capacity() >= 105);
+        assert_eq!(s, "small");
+        assert!(s.try_reserve(usize::MAX).is_err());
+    }
+
+    #[test]
+    fn test_shrink_to() {
+        let mut s = InlinableString::with_capacity(200);
+        s.push_str("abc");
+        s.shrink_to(100);
+        assert!(s.is_heap());
+        assert!(s.capacity() >= 100 && s.capacity() < 200);
+        s.shrink_to(InlineString::CAPACITY);
+        assert!(s.is_inline());
+        assert_eq!(s, "abc");
+
+        // Inline strings have nothing to release.
+        s.shrink_to(0);
+        assert!(s.is_inline());
+    }
+
+    #[test]
+    fn test_repeat() {
+        let s = InlinableString::from("abc");
+        let fits = s.repeat_inlinable(InlineString::CAPACITY / 3);
+        assert_eq!(fits.len(), InlineString::CAPACITY / 3 * 3);
+        assert!(fits.is_inline());
+
+        let spills = s.repeat_inlinable(InlineString::CAPACITY);
+        assert_eq!(spills.len(), InlineString::CAPACITY * 3);
+        assert!(spills.is_heap());
+        assert!(spills.starts_with("abcabc"));
+
+        assert_eq!(s.repeat_inlinable(0), "");
+
+        // `str::repeat` is still reachable and still returns `String`.
+        let plain: String = s.repeat(2);
+        assert_eq!(plain, "abcabc");
+    }
+
+    #[test]
+    fn test_case_conversion() {
+        for &input in &["", "MiXeD", "ὈΔΥΣΣΕΎΣ", "ΑΣ Α", "İstanbul", "ﬃ straße"] {
+            let s = InlinableString::from(input);
+            assert_eq!(s.to_lowercase_inlinable(), &input.to_lowercase()[..]);
+            assert_eq!(s.to_uppercase_inlinable(), &input.to_uppercase()[..]);
+        }
+
+        // Uppercasing 'ΐ' triples its length, pushing the result onto the heap.
+        let s = InlinableString::from("ΐ".repeat(InlineString::CAPACITY / 2).as_str());
+        assert!(s.is_inline());
+        let upper = s.to_uppercase_inlinable();
+        assert_eq!(upper, &s.to_uppercase()[..]);
+        assert!(upper.is_heap());
+        assert!(s.to_lowercase_inlinable().is_inline());
+
+        // The `str` methods are still reachable and still return `String`.
+        let lower: String = s.to_lowercase();
+        assert_eq!(lower, "ΐ".repeat(InlineString::CAPACITY / 2));
+        assert_eq!(s.as_ref().to_uppercase(), upper);
+    }
+
+    #[test]
+    fn test_ascii_case_conversion() {
+        let s = InlinableString::Heap(String::from("MiXeD é"));
+        let lower = s.to_ascii_lowercase_inlinable();
+        assert_eq!(lower, "mixed é");
+        assert!(lower.is_inline());
+        assert_eq!(s.to_ascii_uppercase_inlinable(), "MIXED é");
+
+        let long = InlinableString::from("a string too long to be stored inline");
+        let upper = long.to_ascii_uppercase_inlinable();
+        assert_eq!(upper, "A STRING TOO LONG TO BE STORED INLINE");
+        assert!(upper.is_heap());
+        assert_eq!(upper.to_ascii_lowercase_inlinable(), long);
+
+        // The `str` methods are still reachable and still return `String`.
+        let upper: String = s.to_ascii_uppercase();
+        assert_eq!(upper, "MIXED é");
+    }
+
+    #[test]
+    fn test_replace() {
+        for &(input, from, to) in &[
+            ("", "a", "b"),
+            ("aaa", "a", "bb"),
+            ("aaa", "aa", ""),
+            ("abc", "", "-"),
+            ("héllo", "é", "e"),
+        ] {
+            let s = InlinableString::from(input);
+            assert_eq!(s.replace_inlinable(from, to), &input.replace(from, to)[..]);
+            assert_eq!(
+                s.replacen_inlinable(from, to, 1),
+                &input.replacen(from, to, 1)[..]
+            );
+        }
+
+        let s = InlinableString::from("x".repeat(InlineString::CAPACITY).as_str());
+        assert!(s.replace_inlinable("x", "y").is_inline());
+        let grown = s.replace_inlinable("x", "yy");
+        assert_eq!(grown.len(), InlineString::CAPACITY * 2);
+        assert!(grown.is_heap());
+
+        // The `str` methods are still reachable, with any pattern.
+        let s = InlinableString::from("a b c");
+        let replaced: String = s.replace(' ', "_");
+        assert_eq!(replaced, "a_b_c");
+        assert_eq!(s.replacen(|c: char| c.is_alphabetic(), "x", 2), "x x c");
+    }
+
+    #[test]
+    fn test_as_ptr() {
+        let mut inline = InlinableString::from("inline");
+        assert_eq!(inline.as_ptr(), inline.as_bytes().as_ptr());
+        unsafe { *inline.as_mut_ptr() = b'I' };
+        assert_eq!(inline, "Inline");
+
+        let mut heap = InlinableString::from("a string that is too long to be stored inline");
+        assert_eq!(heap.as_ptr(), heap.as_bytes().as_ptr());
+        unsafe { *heap.as_mut_ptr() = b'A' };
+        assert!(heap.starts_with("A string"));
+    }
+
+    #[test]
+    fn test_into_boxed_str() {
+        let long = InlinableString::from("a string that is too long to be stored inline");
+        let ptr = long.as_ptr();
+        let boxed = long.into_boxed_str();
+        assert_eq!(boxed.as_ptr(), ptr);
+        assert_eq!(&*InlinableString::from("short").into_boxed_str(), "short");
+    }
+
+    #[test]
+    fn test_box_str() {
+        let short: Box<str> = Box::from("short");
+        let s = InlinableString::from(short);
+        assert!(s.is_inline());
+        assert_eq!(Box::<str>::from(s), Box::from("short"));
+
+        let long: Box<str> = Box::from("a string that is too long to be stored inline");
+        let ptr = long.as_ptr();
+        let s = InlinableString::from(long);
+        assert!(s.is_heap());
+        assert_eq!(s.as_ptr(), ptr);
+        let back = Box::<str>::from(s);
+        assert_eq!(back.as_ptr(), ptr);
+    }
+
+    #[test]
+    fn test_into_string() {
+        assert_eq!(String::from(InlinableString::from("inline")), "inline");
+
+        let long = InlinableString::from("a string that is too long to be stored inline");
+        let ptr = long.as_ptr();
+        let string: String = long.into();
+        assert_eq!(string.as_ptr(), ptr);
+    }
+
+    #[test]
+    fn test_inline_string_conversions() {
+        use crate::inline_string::NotEnoughSpaceError;
+        use crate::InlineString;
+        use core::convert::TryFrom;
+
+        let s = InlinableString::from(InlineString::from("inline"));
+        assert!(s.is_inline());
+        assert_eq!(InlineString::try_from(s).unwrap(), "inline");
+
+        let demotable = InlinableString::Heap(String::from("demotable"));
+        assert_eq!(InlineString::try_from(demotable).unwrap(), "demotable");
+
+        let long = InlinableString::from("a string that is too long to be stored inline");
+        let needed = long.len();
+        assert_eq!(
+            InlineString::try_from(long),
+            Err(NotEnoughSpaceError::new(needed, InlineString::CAPACITY))
+        );
+    }
+
+    #[test]
+    fn test_add() {
+        let mut s = InlinableString::from("a") + "b" + 'c';
+        assert_eq!(s, "abc");
+        s += "def";
+        assert_eq!(s, "abcdef");
+        s += &"g".repeat(InlineString::CAPACITY);
+        assert!(s.is_heap());
+        assert_eq!(s.len(), 6 + InlineString::CAPACITY);
+    }
+
+    #[test]
+    fn test_extend_from_within() {
+        let mut s = InlinableString::from("ab€");
+        s.extend_from_within(2..);
+        assert_eq!(s, "ab€€");
+        assert!(s.is_inline());
+
+        while s.is_inline() {
+            s.extend_from_within(..);
+        }
+        assert!(s.len() > InlineString::CAPACITY);
+        assert!(s.starts_with("ab€€ab€€"));
+
+        s.extend_from_within(..=1);
+        assert!(s.ends_with("€ab"));
+    }
+
+    #[test]
+    fn test_collect_owned() {
+        let words = ["ab", "cd", "ef"];
+
+        let s: InlinableString = words.iter().map(|&w| String::from(w)).collect();
+        assert_eq!(s, "abcdef");
+        assert!(s.is_inline());
+        let s: InlinableString = words.iter().map(|&w| Cow::Borrowed(w)).collect();
+        assert_eq!(s, "abcdef");
+        let s: InlinableString = words.iter().map(|&w| Box::<str>::from(w)).collect();
+        assert_eq!(s, "abcdef");
+
+        let mut long = String::with_capacity(100);
+        long.push_str("a string that is too long to be stored inline");
+        let ptr = long.as_ptr();
+        let s: InlinableString = vec![long, String::from("!")].into_iter().collect();
+        assert!(s.ends_with("inline!"));
+        assert_eq!(s.as_ptr(), ptr);
+
+        let empty: InlinableString = core::iter::empty::<String>().collect();
+        assert!(empty.is_empty());
+    }
+
+    #[test]
+    fn test_extend_owned() {
+        let mut s = InlinableString::from("a");
+        s.extend(vec![String::from("b"), String::from("c")]);
+        s.extend(vec![Cow::Borrowed("d"), Cow::Owned(String::from("e"))]);
+        s.extend(vec![Box::<str>::from("f")]);
+        assert_eq!(s, "abcdef");
+        assert!(s.is_inline());
+    }
+
+    #[test]
+    fn test_leak() {
+        let long = InlinableString::from("a string that is too long to be stored inline");
+        let ptr = long.as_ptr();
+        let leaked: &'static mut str = long.leak();
+        assert_eq!(leaked.as_ptr(), ptr);
+
+        let leaked: &'static str = InlinableString::from("short").leak();
+        assert_eq!(leaked, "short");
+    }
+
+    #[test]
+    fn test_into_cow() {
+        fn takes_cow<'a, S: Into<Cow<'a, str>>>(s: S) -> Cow<'a, str> {
+            s.into()
+        }
+
+        assert_eq!(takes_cow(InlinableString::from("inline")), "inline");
+        let long = InlinableString::from("a string that is too long to be stored inline");
+        let ptr = long.as_ptr();
+        let cow = takes_cow(long);
+        assert!(matches!(cow, Cow::Owned(_)));
+        assert_eq!(cow.as_ptr(), ptr);
+    }
+
+    #[test]
+    fn test_vec_conversions() {
+        use core::convert::TryFrom;
+
+        let s = InlinableString::try_from(b"bytes".to_vec()).unwrap();
+        assert_eq!(s, "bytes");
+        assert!(s.is_inline());
+        assert!(InlinableString::try_from(vec![0xff]).is_err());
+
+        let long = InlinableString::from("a string that is too long to be stored inline");
+        let ptr = long.as_ptr();
+        let bytes = Vec::from(long);
+        assert_eq!(bytes.as_ptr(), ptr);
+        assert_eq!(Vec::from(InlinableString::from("abc")), b"abc");
+    }
+
+    #[test]
+    fn test_rotate_chars() {
+        let mut s = InlinableString::from("ab€");
+        s.rotate_left_chars(1);
+        assert_eq!(s, "b€a");
+        s.rotate_right_chars(2);
+        assert_eq!(s, "€ab");
+
+        let mut long = InlinableString::from("€ this string is far too long to fit inline");
+        assert!(matches!(long, InlinableString::Heap(_)));
+        long.rotate_left_chars(2);
+        assert_eq!(long, "this string is far too long to fit inline€ ");
+        long.rotate_right_chars(2);
+        assert_eq!(long, "€ this string is far too long to fit inline");
+    }
+
+    #[test]
+    fn test_capitalize_ext() {
+        let s = InlinableString::from("ßmall");
+        let capitalized = s.capitalize_ext();
+        assert_eq!(capitalized, "SSmall");
+        assert!(matches!(capitalized, InlinableString::Inline(_)));
+    }
+
+    #[test]
+    fn test_sum() {
+        let parts = [InlinableString::from("foo"), InlinableString::from("bar")];
+        let joined: InlinableString = parts.iter().sum();
+        assert_eq!(joined, "foobar");
+
+        let joined: InlinableString = parts.iter().cloned().sum();
+        assert_eq!(joined, "foobar");
+
+        let joined: InlinableString = parts.iter().take(0).sum();
+        assert_eq!(joined, "");
+
+        let long: InlinableString = (0..InlineString::CAPACITY)
+            .map(|_| InlinableString::from("ab"))
+            .sum();
+        assert_eq!(long.len(), 2 * InlineString::CAPACITY);
+
+        let joined: InlinableString = ["foo", "bar"].iter().copied().sum();
+        assert_eq!(joined, "foobar");
+        assert!(joined.is_inline());
+
+        let joined: InlinableString = (1..4).map(|n| n.to_string()).sum();
+        assert_eq!(joined, "123");
+        assert!(joined.is_inline());
+    }
+
     // example generic function where impl FromStr for InlinableString is useful
     fn parse_non_empty<T: FromStr>(s: &str) -> Option<T> {
-        if s.len() == 0 {
+        if s.is_empty() {
             None
         } else {
             let val = T::from_str(s).unwrap_or_else(|_| panic!("unwrap"));
@@ -1015,4 +3142,24 @@ mod benches {
             black_box(s);
         });
     }
+
+    #[bench]
+    fn bench_inlinable_string_retain_large(b: &mut Bencher) {
+        let s = InlinableString::from(LARGE_STR);
+        b.iter(|| {
+            let mut s = black_box(s.clone());
+            s.retain(|c| !c.is_whitespace());
+            black_box(s);
+        });
+    }
+
+    #[bench]
+    fn bench_inlinable_string_retain_bytes_large(b: &mut Bencher) {
+        let s = InlinableString::from(LARGE_STR);
+        b.iter(|| {
+            let mut s = black_box(s.clone());
+            s.retain_bytes(|b: u8| !b.is_ascii_whitespace());
+            black_box(s);
+        });
+    }
 }
@@ -0,0 +1,81 @@
+// Copyright 2015, The inlinable_string crate Developers. See the COPYRIGHT file
+// at the top-level directory of this distribution.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license <LICENSE-MIT
+// or http://opensource.org/licenses/MIT>, at your option. This file may not be
+// copied, modified, or distributed except according to those terms.
+
+//! Implementations of the unstable `Pattern` API, so that references to this
+//! crate's string types can be searched for directly, as `&String` can.
+
+use crate::{InlinableString, InlineString};
+use core::str::pattern::{Pattern, ReverseSearcher};
+
+macro_rules! impl_pattern {
+    ($ty:ty) => {
+        impl<'b> Pattern for &'b $ty {
+            type Searcher<'a> = <&'b str as Pattern>::Searcher<'a>;
+
+            #[inline]
+            fn into_searcher(self, haystack: &str) -> Self::Searcher<'_> {
+                self[..].into_searcher(haystack)
+            }
+
+            #[inline]
+            fn is_contained_in(self, haystack: &str) -> bool {
+                self[..].is_contained_in(haystack)
+            }
+
+            #[inline]
+            fn is_prefix_of(self, haystack: &str) -> bool {
+                self[..].is_prefix_of(haystack)
+            }
+
+            #[inline]
+            fn strip_prefix_of(self, haystack: &str) -> Option<&str> {
+                self[..].strip_prefix_of(haystack)
+            }
+
+            #[inline]
+            fn is_suffix_of<'a>(self, haystack: &'a str) -> bool
+            where
+                Self::Searcher<'a>: ReverseSearcher<'a>,
+            {
+                self[..].is_suffix_of(haystack)
+            }
+
+            #[inline]
+            fn strip_suffix_of<'a>(self, haystack: &'a str) -> Option<&'a str>
+            where
+                Self::Searcher<'a>: ReverseSearcher<'a>,
+            {
+                self[..].strip_suffix_of(haystack)
+            }
+        }
+    };
+}
+
+impl_pattern! { InlinableString }
+impl_pattern! { InlineString }
+
+#[cfg(test)]
+mod tests {
+    use crate::{InlinableString, InlineString};
+    use alloc::vec::Vec;
+
+    #[test]
+    fn test_pattern() {
+        let needle = InlinableString::from(", ");
+        let haystack = "a, b, c";
+        assert_eq!(haystack.find(&needle), Some(1));
+        assert_eq!(haystack.split(&needle).collect::<Vec<_>>(), ["a", "b", "c"]);
+        assert_eq!(haystack.rfind(&needle), Some(4));
+
+        let prefix = InlineString::from("a, ");
+        assert!(haystack.starts_with(&prefix));
+        assert_eq!(haystack.strip_prefix(&prefix), Some("b, c"));
+        assert!(haystack.contains(&InlineString::from("b")));
+        assert!(!haystack.ends_with(&InlineString::from("b")));
+    }
+}
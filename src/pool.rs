@@ -0,0 +1,235 @@
+// Copyright 2015, The inlinable_string crate Developers. See the COPYRIGHT file
+// at the top-level directory of this distribution.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license <LICENSE-MIT
+// or http://opensource.org/licenses/MIT>, at your option. This file may not be
+// copied, modified, or distributed except according to those terms.
+
+//! An opt-in, thread-local pool of heap buffers for `InlinableString`.
+//!
+//! With the `pool` feature enabled, an `InlinableString` that is promoted from
+//! inline storage to the heap first tries to reuse a buffer from the current
+//! thread's `StringPool`. A string demoted back to inline storage by
+//! `shrink_to_fit` returns its old buffer to the pool instead of freeing it.
+//! This smooths allocator pressure in loops whose strings only briefly exceed
+//! `InlinableString::INLINE_CAPACITY`.
+//!
+//! `InlinableString` does not implement `Drop`, because that would forbid
+//! moving the `String` out of the public `Heap` variant. Call
+//! `InlinableString::recycle` to hand a string's buffer back to the pool
+//! when you are done with it.
+//!
+//! The pool is thread-local, so this module is not available with `no_std`.
+//!
+//! # Examples
+//!
+//! ```
+//! use inlinable_string::{InlinableString, StringExt, StringPool};
+//!
+//! StringPool::give(String::with_capacity(128));
+//! assert_eq!(StringPool::len(), 1);
+//!
+//! // Promotion reuses the pooled buffer.
+//! let mut s = InlinableString::from("short");
+//! s.push_str(" but soon long enough to need heap allocation");
+//! assert!(s.capacity() >= 128);
+//! assert_eq!(StringPool::len(), 0);
+//!
+//! // And recycling returns it.
+//! s.recycle();
+//! assert_eq!(StringPool::len(), 1);
+//! ```
+
+use crate::InlineString;
+use std::cell::RefCell;
+use std::collections::TryReserveError;
+
+/// The maximum number of buffers the pool keeps per thread. Buffers given to
+/// a full pool are freed.
+pub const MAX_POOLED_BUFFERS: usize = 32;
+
+/// The maximum capacity (in bytes) of a buffer the pool will keep. Larger
+/// buffers are freed rather than pinned in the pool.
+pub const MAX_POOLED_CAPACITY: usize = 4096;
+
+thread_local! {
+    static POOL: RefCell<Vec<String>> = const { RefCell::new(Vec::new()) };
+}
+
+/// A thread-local freelist of `String` buffers.
+///
+/// See the [module level documentation](./index.html) for more.
+#[derive(Debug)]
+pub struct StringPool {
+    _private: (),
+}
+
+impl StringPool {
+    /// Returns an empty `String` with at least `min_capacity` bytes of
+    /// capacity, reusing a pooled buffer if one is large enough and
+    /// allocating a new one otherwise.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use inlinable_string::StringPool;
+    ///
+    /// let s = StringPool::take(64);
+    /// assert!(s.is_empty());
+    /// assert!(s.capacity() >= 64);
+    /// ```
+    pub fn take(min_capacity: usize) -> String {
+        StringPool::take_pooled(min_capacity)
+            .unwrap_or_else(|| String::with_capacity(min_capacity))
+    }
+
+    /// Like `take`, but reports allocation failure instead of aborting when
+    /// no pooled buffer is large enough.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use inlinable_string::StringPool;
+    ///
+    /// assert!(StringPool::try_take(64).unwrap().capacity() >= 64);
+    /// assert!(StringPool::try_take(usize::MAX).is_err());
+    /// ```
+    pub fn try_take(min_capacity: usize) -> Result<String, TryReserveError> {
+        match StringPool::take_pooled(min_capacity) {
+            Some(s) => Ok(s),
+            None => {
+                let mut s = String::new();
+                s.try_reserve_exact(min_capacity)?;
+                Ok(s)
+            }
+        }
+    }
+
+    fn take_pooled(min_capacity: usize) -> Option<String> {
+        POOL.with(|pool| {
+            let mut pool = pool.borrow_mut();
+            let idx = pool.iter().position(|s| s.capacity() >= min_capacity)?;
+            Some(pool.swap_remove(idx))
+        })
+    }
+
+    /// Clears `string` and keeps its buffer for reuse by this thread. Buffers
+    /// no larger than `InlinableString::INLINE_CAPACITY` or larger than
+    /// `MAX_POOLED_CAPACITY`, and buffers given to a full pool, are freed
+    /// instead.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use inlinable_string::StringPool;
+    ///
+    /// StringPool::clear();
+    /// StringPool::give(String::from("some contents that will be cleared"));
+    /// assert_eq!(StringPool::len(), 1);
+    /// assert!(StringPool::take(1).is_empty());
+    /// ```
+    pub fn give(mut string: String) {
+        let capacity = string.capacity();
+        if capacity <= InlineString::CAPACITY || capacity > MAX_POOLED_CAPACITY {
+            return;
+        }
+        string.clear();
+        POOL.with(|pool| {
+            let mut pool = pool.borrow_mut();
+            if pool.len() < MAX_POOLED_BUFFERS {
+                pool.push(string);
+            }
+        });
+    }
+
+    /// Returns the number of buffers currently pooled on this thread.
+    pub fn len() -> usize {
+        POOL.with(|pool| pool.borrow().len())
+    }
+
+    /// Returns true if no buffers are pooled on this thread.
+    pub fn is_empty() -> bool {
+        StringPool::len() == 0
+    }
+
+    /// Frees every buffer pooled on this thread.
+    pub fn clear() {
+        POOL.with(|pool| pool.borrow_mut().clear());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{StringPool, MAX_POOLED_BUFFERS, MAX_POOLED_CAPACITY};
+    use crate::{InlinableString, InlineString, StringExt};
+
+    #[test]
+    fn test_take_and_give() {
+        StringPool::clear();
+        assert!(StringPool::is_empty());
+
+        // Too small or too big to be worth keeping.
+        StringPool::give(String::with_capacity(InlineString::CAPACITY));
+        StringPool::give(String::with_capacity(MAX_POOLED_CAPACITY + 1));
+        assert!(StringPool::is_empty());
+
+        StringPool::give(String::with_capacity(100));
+        assert_eq!(StringPool::len(), 1);
+
+        // Not big enough for this request, so a fresh buffer is allocated.
+        let big = StringPool::take(200);
+        assert!(big.capacity() >= 200);
+        assert_eq!(StringPool::len(), 1);
+
+        let reused = StringPool::take(50);
+        assert!(reused.capacity() >= 100);
+        assert!(StringPool::is_empty());
+
+        for _ in 0..MAX_POOLED_BUFFERS + 1 {
+            StringPool::give(String::with_capacity(100));
+        }
+        assert_eq!(StringPool::len(), MAX_POOLED_BUFFERS);
+        StringPool::clear();
+    }
+
+    #[test]
+    fn test_promote_and_demote() {
+        StringPool::clear();
+        StringPool::give(String::with_capacity(256));
+
+        let mut s = InlinableString::from("small");
+        s.push_str("a string that no longer fits in inline storage");
+        assert!(s.capacity() >= 256);
+        assert!(StringPool::is_empty());
+
+        s.truncate(5);
+        s.shrink_to_fit();
+        assert_eq!(s, "small");
+        assert!(matches!(s, InlinableString::Inline(_)));
+        assert_eq!(StringPool::len(), 1);
+
+        let heap = InlinableString::with_capacity(200);
+        assert!(StringPool::is_empty());
+        heap.recycle();
+        assert_eq!(StringPool::len(), 1);
+        StringPool::clear();
+    }
+
+    #[test]
+    fn test_try_reserve_uses_pool() {
+        StringPool::clear();
+        StringPool::give(String::with_capacity(256));
+
+        let mut s = InlinableString::from("small");
+        s.try_reserve(100).unwrap();
+        assert!(s.capacity() >= 256);
+        assert!(StringPool::is_empty());
+
+        let mut s = InlinableString::from("small");
+        assert!(s.try_reserve_exact(usize::MAX).is_err());
+        assert!(s.is_inline());
+        assert!(StringPool::try_take(usize::MAX).is_err());
+        StringPool::clear();
+    }
+}
@@ -0,0 +1,110 @@
+// Copyright 2015, The inlinable_string crate Developers. See the COPYRIGHT file
+// at the top-level directory of this distribution.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license <LICENSE-MIT
+// or http://opensource.org/licenses/MIT>, at your option. This file may not be
+// copied, modified, or distributed except according to those terms.
+
+use crate::{InlinableString, InlineString};
+use alloc::boxed::Box;
+use alloc::string::String;
+use core::convert::TryFrom;
+use core::iter;
+use quickcheck::{Arbitrary, Gen};
+
+/// Generates both variants: short contents are stored inline or on the heap
+/// at random. Shrinking first moves a short heap string inline, then shrinks
+/// the contents, storing each candidate inline whenever it fits.
+impl Arbitrary for InlinableString {
+    fn arbitrary(g: &mut Gen) -> InlinableString {
+        let contents = String::arbitrary(g);
+        if bool::arbitrary(g) {
+            InlinableString::Heap(contents)
+        } else {
+            InlinableString::from(contents)
+        }
+    }
+
+    fn shrink(&self) -> Box<dyn Iterator<Item = InlinableString>> {
+        let moved_inline = match *self {
+            InlinableString::Heap(ref s) => InlineString::try_from(s).ok(),
+            InlinableString::Inline(_) => None,
+        };
+        let shorter = String::from(&**self).shrink().map(InlinableString::from);
+        Box::new(
+            moved_inline
+                .map(InlinableString::Inline)
+                .into_iter()
+                .chain(shorter),
+        )
+    }
+}
+
+/// Generates as much of an arbitrary `String` as fits inline, cut at a
+/// character boundary.
+impl Arbitrary for InlineString {
+    fn arbitrary(g: &mut Gen) -> InlineString {
+        let mut string = InlineString::new();
+        string.push_str_truncating(&String::arbitrary(g));
+        string
+    }
+
+    fn shrink(&self) -> Box<dyn Iterator<Item = InlineString>> {
+        if self.is_empty() {
+            return Box::new(iter::empty());
+        }
+        Box::new(
+            String::from(&**self)
+                .shrink()
+                .filter_map(|s| InlineString::try_from(s).ok()),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{InlinableString, InlineString, StringExt};
+    use alloc::string::String;
+    use alloc::vec::Vec;
+    use quickcheck::{Arbitrary, Gen, QuickCheck};
+
+    #[test]
+    fn test_arbitrary_variants() {
+        let mut g = Gen::new(2 * InlineString::CAPACITY);
+        let strings: Vec<InlinableString> =
+            (0..200).map(|_| Arbitrary::arbitrary(&mut g)).collect();
+        assert!(strings.iter().any(|s| s.is_inline()));
+        assert!(strings
+            .iter()
+            .any(|s| s.is_heap() && s.len() <= InlineString::CAPACITY));
+        assert!(strings.iter().any(|s| s.len() > InlineString::CAPACITY));
+    }
+
+    #[test]
+    fn test_shrink_moves_inline() {
+        let s = InlinableString::Heap(String::from("short"));
+        let mut candidates = s.shrink();
+        let first = candidates.next().unwrap();
+        assert!(first.is_inline());
+        assert_eq!(first, "short");
+        assert!(candidates.all(|c| c.is_inline() && c.len() <= 5 && c != "short"));
+
+        assert!(InlineString::new().shrink().next().is_none());
+        assert!(InlineString::from("ab")
+            .shrink()
+            .all(|c| c.len() <= 2 && c != "ab"));
+    }
+
+    #[test]
+    fn test_property() {
+        fn prop(s: InlinableString, t: InlineString) -> bool {
+            let mut joined = s.clone();
+            joined.push_str(&t);
+            joined.len() == s.len() + t.len() && joined.starts_with(&*s)
+        }
+        QuickCheck::new()
+            .tests(100)
+            .quickcheck(prop as fn(InlinableString, InlineString) -> bool);
+    }
+}
@@ -0,0 +1,108 @@
+use alloc::string::String;
+use crate::inline_string::NotEnoughSpaceError;
+use crate::{InlinableString, InlineString};
+use rand::distributions::{Alphanumeric, Distribution};
+use rand::Rng;
+
+impl InlineString {
+    /// Creates a string of `len` random ASCII alphanumeric characters
+    /// (`[A-Za-z0-9]`) drawn from `rng`.
+    ///
+    /// This method requires the `rand` feature.
+    ///
+    /// # Failure
+    ///
+    /// Returns `NotEnoughSpaceError` if `len` is greater than
+    /// `InlineString::CAPACITY`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use inlinable_string::InlineString;
+    ///
+    /// let id = InlineString::random_alphanumeric(&mut rand::thread_rng(), 12).unwrap();
+    /// assert_eq!(id.len(), 12);
+    /// assert!(id.bytes().all(|b| b.is_ascii_alphanumeric()));
+    /// ```
+    pub fn random_alphanumeric<R: Rng + ?Sized>(
+        rng: &mut R,
+        len: usize,
+    ) -> Result<InlineString, NotEnoughSpaceError> {
+        if len > InlineString::CAPACITY {
+            return Err(NotEnoughSpaceError::new(len, InlineString::CAPACITY));
+        }
+
+        let mut s = InlineString::new();
+        for byte in Alphanumeric.sample_iter(rng).take(len) {
+            s.push(char::from(byte))?;
+        }
+        Ok(s)
+    }
+}
+
+/// A distribution of `InlinableString`s made of a fixed number of random
+/// ASCII alphanumeric characters (`[A-Za-z0-9]`).
+///
+/// Strings no longer than `InlineString::CAPACITY` are generated directly
+/// into inline storage.
+///
+/// This type requires the `rand` feature.
+///
+/// # Examples
+///
+/// ```
+/// use inlinable_string::{AlphanumericString, InlinableString};
+/// use rand::Rng;
+///
+/// let token: InlinableString = rand::thread_rng().sample(AlphanumericString(16));
+/// assert_eq!(token.len(), 16);
+/// ```
+#[derive(Clone, Copy, Debug)]
+pub struct AlphanumericString(pub usize);
+
+impl Distribution<InlinableString> for AlphanumericString {
+    fn sample<R: Rng + ?Sized>(&self, rng: &mut R) -> InlinableString {
+        match InlineString::random_alphanumeric(rng, self.0) {
+            Ok(s) => InlinableString::Inline(s),
+            Err(_) => InlinableString::Heap(
+                Alphanumeric
+                    .sample_iter(rng)
+                    .take(self.0)
+                    .map(char::from)
+                    .collect::<String>(),
+            ),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::AlphanumericString;
+    use crate::{InlinableString, InlineString};
+    use rand::rngs::mock::StepRng;
+    use rand::Rng;
+
+    #[test]
+    fn test_random_alphanumeric() {
+        let mut rng = StepRng::new(0, 0x9e37_79b9_7f4a_7c15);
+        let s = InlineString::random_alphanumeric(&mut rng, InlineString::CAPACITY).unwrap();
+        assert_eq!(s.len(), InlineString::CAPACITY);
+        assert!(s.bytes().all(|b| b.is_ascii_alphanumeric()));
+
+        assert!(InlineString::random_alphanumeric(&mut rng, InlineString::CAPACITY + 1).is_err());
+    }
+
+    #[test]
+    fn test_distribution() {
+        let mut rng = StepRng::new(0, 0x9e37_79b9_7f4a_7c15);
+
+        let short: InlinableString = rng.sample(AlphanumericString(8));
+        assert_eq!(short.len(), 8);
+        assert!(matches!(short, InlinableString::Inline(_)));
+
+        let long: InlinableString = rng.sample(AlphanumericString(InlineString::CAPACITY + 1));
+        assert_eq!(long.len(), InlineString::CAPACITY + 1);
+        assert!(matches!(long, InlinableString::Heap(_)));
+        assert!(long.bytes().all(|b| b.is_ascii_alphanumeric()));
+    }
+}
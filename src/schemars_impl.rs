@@ -0,0 +1,96 @@
+// Copyright 2015, The inlinable_string crate Developers. See the COPYRIGHT file
+// at the top-level directory of this distribution.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license <LICENSE-MIT
+// or http://opensource.org/licenses/MIT>, at your option. This file may not be
+// copied, modified, or distributed except according to those terms.
+
+use crate::{InlinableString, InlineString};
+use alloc::borrow::Cow;
+use alloc::string::String;
+use schemars::{json_schema, JsonSchema, Schema, SchemaGenerator};
+
+/// Same schema as `String`.
+impl JsonSchema for InlinableString {
+    #[inline]
+    fn inline_schema() -> bool {
+        String::inline_schema()
+    }
+
+    #[inline]
+    fn schema_name() -> Cow<'static, str> {
+        String::schema_name()
+    }
+
+    #[inline]
+    fn schema_id() -> Cow<'static, str> {
+        String::schema_id()
+    }
+
+    #[inline]
+    fn json_schema(generator: &mut SchemaGenerator) -> Schema {
+        String::json_schema(generator)
+    }
+}
+
+/// A string with a `maxLength` of `InlineString::CAPACITY`.
+///
+/// JSON Schema measures length in characters while the capacity is in bytes,
+/// so strings containing multi-byte characters can pass validation and still
+/// not fit.
+impl JsonSchema for InlineString {
+    #[inline]
+    fn inline_schema() -> bool {
+        true
+    }
+
+    #[inline]
+    fn schema_name() -> Cow<'static, str> {
+        Cow::Borrowed("InlineString")
+    }
+
+    #[inline]
+    fn schema_id() -> Cow<'static, str> {
+        Cow::Borrowed("inlinable_string::InlineString")
+    }
+
+    fn json_schema(_: &mut SchemaGenerator) -> Schema {
+        json_schema!({
+            "type": "string",
+            "maxLength": InlineString::CAPACITY,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{InlinableString, InlineString};
+    use alloc::string::String;
+    use schemars::{json_schema, schema_for, JsonSchema};
+
+    #[test]
+    fn test_schemas() {
+        assert_eq!(schema_for!(InlinableString), schema_for!(String));
+        assert_eq!(
+            schema_for!(InlineString),
+            json_schema!({
+                "$schema": "https://json-schema.org/draft/2020-12/schema",
+                "title": "InlineString",
+                "type": "string",
+                "maxLength": InlineString::CAPACITY,
+            })
+        );
+    }
+
+    #[test]
+    fn test_subschemas_are_inlined() {
+        let mut generator = schemars::SchemaGenerator::default();
+        let name = generator.subschema_for::<InlinableString>();
+        let code = generator.subschema_for::<InlineString>();
+        assert_eq!(name, json_schema!({ "type": "string" }));
+        assert_eq!(code.get("maxLength"), Some(&InlineString::CAPACITY.into()));
+        assert!(generator.definitions().is_empty());
+        assert!(InlineString::inline_schema());
+    }
+}
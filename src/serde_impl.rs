@@ -34,6 +34,13 @@ impl<'de> Deserialize<'de> for InlinableString {
                 Ok(v.into())
             }
 
+            fn visit_borrowed_str<E>(self, v: &'de str) -> Result<Self::Value, E>
+            where
+                E: DeError,
+            {
+                Ok(v.into())
+            }
+
             fn visit_string<E>(self, v: String) -> Result<Self::Value, E>
             where
                 E: DeError,
@@ -44,12 +51,48 @@ impl<'de> Deserialize<'de> for InlinableString {
 
         deserializer.deserialize_str(InlinableStringVisitor)
     }
+
+    fn deserialize_in_place<D>(deserializer: D, place: &mut Self) -> Result<(), D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        /// Writes into the existing string, keeping its heap buffer when it
+        /// is large enough.
+        struct InPlaceVisitor<'a>(&'a mut InlinableString);
+
+        impl<'a, 'de> Visitor<'de> for InPlaceVisitor<'a> {
+            type Value = ();
+
+            fn expecting(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+                formatter.write_str("a string")
+            }
+
+            fn visit_str<E>(self, v: &str) -> Result<Self::Value, E>
+            where
+                E: DeError,
+            {
+                self.0.assign(v);
+                Ok(())
+            }
+
+            fn visit_string<E>(self, v: String) -> Result<Self::Value, E>
+            where
+                E: DeError,
+            {
+                // The deserializer has already allocated, so take its buffer.
+                *self.0 = v.into();
+                Ok(())
+            }
+        }
+
+        deserializer.deserialize_str(InPlaceVisitor(place))
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use crate::InlinableString;
-    use serde_test::{assert_tokens, Token};
+    use serde_test::{assert_de_tokens, assert_tokens, Token};
 
     #[test]
     fn test_ser_de() {
@@ -57,4 +100,92 @@ mod tests {
 
         assert_tokens(&s, &[Token::String("small")]);
     }
+
+    #[test]
+    fn test_de_borrowed() {
+        let s = InlinableString::from("small");
+        assert_de_tokens(&s, &[Token::BorrowedStr("small")]);
+
+        let s = InlinableString::from("a borrowed string too long to be stored inline");
+        assert_de_tokens(&s, &[Token::BorrowedStr("a borrowed string too long to be stored inline")]);
+    }
+
+    #[test]
+    fn test_de_in_place() {
+        use crate::StringExt;
+        use serde::de::value::{Error, StrDeserializer};
+        use serde::de::IntoDeserializer;
+        use serde::Deserialize;
+
+        let mut place = InlinableString::with_capacity(100);
+        let ptr = place.as_ptr();
+
+        let de: StrDeserializer<'_, Error> = "first message".into_deserializer();
+        InlinableString::deserialize_in_place(de, &mut place).unwrap();
+        assert_eq!(place, "first message");
+
+        let long = "a second message that is too long to be stored inline";
+        let de: StrDeserializer<'_, Error> = long.into_deserializer();
+        InlinableString::deserialize_in_place(de, &mut place).unwrap();
+        assert_eq!(place, long);
+        assert_eq!(place.as_ptr(), ptr);
+
+        let mut place = InlinableString::new();
+        let de: StrDeserializer<'_, Error> = "inline".into_deserializer();
+        InlinableString::deserialize_in_place(de, &mut place).unwrap();
+        assert_eq!(place, "inline");
+        assert!(place.is_inline());
+    }
+}
+
+#[cfg(test)]
+#[cfg(feature = "nightly")]
+mod benches {
+    #[cfg(feature = "no_std")]
+    use alloc::string::String;
+    use alloc::collections::BTreeMap;
+    use alloc::vec::Vec;
+    use crate::InlinableString;
+    use serde::de::value::{BorrowedStrDeserializer, Error, MapDeserializer};
+    use serde::Deserialize;
+    use test::{black_box, Bencher};
+
+    /// A document with many short keys and values, like a typical JSON
+    /// object of identifiers.
+    fn short_entries() -> Vec<(String, String)> {
+        (0..1000)
+            .map(|i| (format!("key{}", i), format!("value{}", i)))
+            .collect()
+    }
+
+    /// Deserializes `entries` as a map through the borrowed-string path,
+    /// which is the only path some zero-copy formats take.
+    fn deserialize_map<'de, T>(entries: &'de [(String, String)]) -> BTreeMap<T, T>
+    where
+        T: Deserialize<'de> + Ord,
+    {
+        let deserializer = MapDeserializer::<_, Error>::new(entries.iter().map(|(k, v)| {
+            (
+                BorrowedStrDeserializer::new(k.as_str()),
+                BorrowedStrDeserializer::new(v.as_str()),
+            )
+        }));
+        BTreeMap::deserialize(deserializer).unwrap()
+    }
+
+    #[bench]
+    fn bench_std_string_deserialize_map_of_short_strings(b: &mut Bencher) {
+        let entries = short_entries();
+        b.iter(|| {
+            black_box(deserialize_map::<String>(&entries));
+        });
+    }
+
+    #[bench]
+    fn bench_inlinable_string_deserialize_map_of_short_strings(b: &mut Bencher) {
+        let entries = short_entries();
+        b.iter(|| {
+            black_box(deserialize_map::<InlinableString>(&entries));
+        });
+    }
 }
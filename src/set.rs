@@ -0,0 +1,492 @@
+// Copyright 2015, The inlinable_string crate Developers. See the COPYRIGHT file
+// at the top-level directory of this distribution.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license <LICENSE-MIT
+// or http://opensource.org/licenses/MIT>, at your option. This file may not be
+// copied, modified, or distributed except according to those terms.
+
+//! A sorted set of strings tuned for large numbers of short keys, such as
+//! identifiers.
+//!
+//! `InlineStringSet` keeps its keys in sorted blocks of up to
+//! `MAX_BLOCK_LEN` entries. The first key of each block is stored whole, and
+//! every following key is front-coded: it stores only the length of the prefix
+//! it shares with the previous key, plus the remaining suffix as an
+//! `InlinableString`. For sets of identifiers that share namespaces or stems,
+//! the suffixes are short enough to live inline, so most keys cost no heap
+//! allocation of their own.
+//!
+//! Because keys are stored compressed, the set hands out owned
+//! `InlinableString`s rather than `&str`s when iterating.
+//!
+//! This module requires the `set` feature.
+//!
+//! # Examples
+//!
+//! ```
+//! use inlinable_string::InlineStringSet;
+//!
+//! let mut set = InlineStringSet::new();
+//! assert!(set.insert("std::vec::Vec"));
+//! assert!(set.insert("std::string::String"));
+//! assert!(set.insert("std::string::ToString"));
+//! assert!(!set.insert("std::vec::Vec"));
+//!
+//! assert!(set.contains("std::string::String"));
+//! assert_eq!(set.len(), 3);
+//!
+//! let keys: Vec<_> = set.iter().collect();
+//! assert_eq!(keys, ["std::string::String", "std::string::ToString", "std::vec::Vec"]);
+//! ```
+
+use crate::{InlinableString, StringExt};
+use alloc::vec::Vec;
+use core::cmp::Ordering;
+use core::fmt;
+use core::iter;
+
+/// The maximum number of keys stored in one front-coded block. Larger blocks
+/// compress better but make lookups and updates decode more keys.
+pub const MAX_BLOCK_LEN: usize = 16;
+
+/// A key stored relative to the key before it in its block.
+#[derive(Clone, Debug)]
+struct Entry {
+    /// The number of leading bytes shared with the previous key. Always a
+    /// character boundary in both keys.
+    shared: usize,
+    /// The rest of the key after the shared prefix.
+    suffix: InlinableString,
+}
+
+/// A sorted, non-empty run of front-coded keys.
+#[derive(Clone, Debug)]
+struct Block {
+    first: InlinableString,
+    rest: Vec<Entry>,
+}
+
+impl Block {
+    /// Front-codes `keys`, which must be sorted, unique, and non-empty.
+    fn encode(keys: &[InlinableString]) -> Block {
+        let rest = keys
+            .windows(2)
+            .map(|pair| {
+                let (prev, key) = (&pair[0][..], &pair[1][..]);
+                let mut shared = prev
+                    .bytes()
+                    .zip(key.bytes())
+                    .take_while(|&(a, b)| a == b)
+                    .count();
+                while !key.is_char_boundary(shared) {
+                    shared -= 1;
+                }
+                Entry {
+                    shared,
+                    suffix: InlinableString::from(&key[shared..]),
+                }
+            })
+            .collect();
+        Block {
+            first: keys[0].clone(),
+            rest,
+        }
+    }
+
+    fn len(&self) -> usize {
+        self.rest.len() + 1
+    }
+
+    fn keys(&self) -> BlockKeys<'_> {
+        BlockKeys {
+            block: self,
+            current: None,
+            next_entry: 0,
+        }
+    }
+
+    fn decode(&self) -> Vec<InlinableString> {
+        let mut keys = Vec::with_capacity(self.len());
+        let mut block_keys = self.keys();
+        while let Some(key) = block_keys.next_key() {
+            keys.push(InlinableString::from(key));
+        }
+        keys
+    }
+}
+
+/// Decodes the keys of a block in order, reusing one buffer.
+struct BlockKeys<'a> {
+    block: &'a Block,
+    current: Option<InlinableString>,
+    next_entry: usize,
+}
+
+impl<'a> BlockKeys<'a> {
+    fn next_key(&mut self) -> Option<&str> {
+        match self.current {
+            None => {
+                self.current = Some(self.block.first.clone());
+            }
+            Some(ref mut current) => {
+                let entry = self.block.rest.get(self.next_entry)?;
+                current.truncate(entry.shared);
+                current.push_str(&entry.suffix);
+                self.next_entry += 1;
+            }
+        }
+        self.current.as_deref()
+    }
+}
+
+/// A sorted set of strings that front-codes its keys in small blocks.
+///
+/// See the [module level documentation](./index.html) for more.
+#[derive(Clone, Default)]
+pub struct InlineStringSet {
+    blocks: Vec<Block>,
+    len: usize,
+}
+
+impl InlineStringSet {
+    /// Creates an empty set.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use inlinable_string::InlineStringSet;
+    ///
+    /// let set = InlineStringSet::new();
+    /// assert!(set.is_empty());
+    /// ```
+    #[inline]
+    pub fn new() -> InlineStringSet {
+        InlineStringSet {
+            blocks: Vec::new(),
+            len: 0,
+        }
+    }
+
+    /// Returns the number of keys in the set.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use inlinable_string::InlineStringSet;
+    ///
+    /// let set: InlineStringSet = ["a", "b", "a"].iter().cloned().collect();
+    /// assert_eq!(set.len(), 2);
+    /// ```
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Returns true if the set contains no keys.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use inlinable_string::InlineStringSet;
+    ///
+    /// let mut set = InlineStringSet::new();
+    /// assert!(set.is_empty());
+    /// set.insert("a");
+    /// assert!(!set.is_empty());
+    /// ```
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Removes every key from the set.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use inlinable_string::InlineStringSet;
+    ///
+    /// let mut set: InlineStringSet = ["a", "b"].iter().cloned().collect();
+    /// set.clear();
+    /// assert!(set.is_empty());
+    /// ```
+    #[inline]
+    pub fn clear(&mut self) {
+        self.blocks.clear();
+        self.len = 0;
+    }
+
+    /// Returns the index of the block that `key` belongs in. Only meaningful
+    /// when the set is non-empty.
+    fn block_for(&self, key: &str) -> usize {
+        let after = self.blocks.partition_point(|block| &block.first[..] <= key);
+        after.saturating_sub(1)
+    }
+
+    /// Returns true if the set contains `key`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use inlinable_string::InlineStringSet;
+    ///
+    /// let set: InlineStringSet = ["foo", "foobar"].iter().cloned().collect();
+    /// assert!(set.contains("foobar"));
+    /// assert!(!set.contains("fooba"));
+    /// ```
+    pub fn contains(&self, key: &str) -> bool {
+        if self.is_empty() {
+            return false;
+        }
+        let mut keys = self.blocks[self.block_for(key)].keys();
+        while let Some(candidate) = keys.next_key() {
+            match candidate.cmp(key) {
+                Ordering::Less => {}
+                Ordering::Equal => return true,
+                Ordering::Greater => return false,
+            }
+        }
+        false
+    }
+
+    /// Adds `key` to the set. Returns true if it was not already present.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use inlinable_string::InlineStringSet;
+    ///
+    /// let mut set = InlineStringSet::new();
+    /// assert!(set.insert("foo"));
+    /// assert!(!set.insert("foo"));
+    /// ```
+    pub fn insert(&mut self, key: &str) -> bool {
+        if self.is_empty() {
+            self.blocks.push(Block::encode(&[InlinableString::from(key)]));
+            self.len = 1;
+            return true;
+        }
+
+        let idx = self.block_for(key);
+        let mut keys = self.blocks[idx].decode();
+        match keys.binary_search_by(|probe| probe[..].cmp(key)) {
+            Ok(_) => return false,
+            Err(pos) => keys.insert(pos, InlinableString::from(key)),
+        }
+
+        if keys.len() > MAX_BLOCK_LEN {
+            let tail = keys.split_off(keys.len() / 2);
+            self.blocks.insert(idx + 1, Block::encode(&tail));
+        }
+        self.blocks[idx] = Block::encode(&keys);
+        self.len += 1;
+        true
+    }
+
+    /// Removes `key` from the set. Returns true if it was present.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use inlinable_string::InlineStringSet;
+    ///
+    /// let mut set: InlineStringSet = ["foo"].iter().cloned().collect();
+    /// assert!(set.remove("foo"));
+    /// assert!(!set.remove("foo"));
+    /// ```
+    pub fn remove(&mut self, key: &str) -> bool {
+        if self.is_empty() {
+            return false;
+        }
+
+        let idx = self.block_for(key);
+        let mut keys = self.blocks[idx].decode();
+        match keys.binary_search_by(|probe| probe[..].cmp(key)) {
+            Ok(pos) => {
+                keys.remove(pos);
+            }
+            Err(_) => return false,
+        }
+
+        if keys.is_empty() {
+            self.blocks.remove(idx);
+        } else {
+            self.blocks[idx] = Block::encode(&keys);
+        }
+        self.len -= 1;
+        true
+    }
+
+    /// Returns an iterator over the keys of the set, in ascending order.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use inlinable_string::InlineStringSet;
+    ///
+    /// let set: InlineStringSet = ["b", "c", "a"].iter().cloned().collect();
+    /// let keys: Vec<_> = set.iter().collect();
+    /// assert_eq!(keys, ["a", "b", "c"]);
+    /// ```
+    pub fn iter(&self) -> Iter<'_> {
+        Iter {
+            blocks: self.blocks.iter(),
+            current: None,
+            remaining: self.len,
+        }
+    }
+}
+
+// Two sets with the same keys can split them into blocks differently, so
+// compare keys rather than deriving.
+impl PartialEq for InlineStringSet {
+    fn eq(&self, other: &InlineStringSet) -> bool {
+        self.len == other.len && self.iter().eq(other.iter())
+    }
+}
+
+impl Eq for InlineStringSet {}
+
+impl fmt::Debug for InlineStringSet {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_set().entries(self.iter()).finish()
+    }
+}
+
+impl<'a> iter::FromIterator<&'a str> for InlineStringSet {
+    fn from_iter<I: IntoIterator<Item = &'a str>>(iter: I) -> InlineStringSet {
+        let mut set = InlineStringSet::new();
+        set.extend(iter);
+        set
+    }
+}
+
+impl<'a> Extend<&'a str> for InlineStringSet {
+    fn extend<I: IntoIterator<Item = &'a str>>(&mut self, iterable: I) {
+        for key in iterable {
+            self.insert(key);
+        }
+    }
+}
+
+impl<'a> IntoIterator for &'a InlineStringSet {
+    type Item = InlinableString;
+    type IntoIter = Iter<'a>;
+
+    fn into_iter(self) -> Iter<'a> {
+        self.iter()
+    }
+}
+
+/// An iterator over the keys of an `InlineStringSet`, in ascending order.
+///
+/// Created by `InlineStringSet::iter`.
+pub struct Iter<'a> {
+    blocks: core::slice::Iter<'a, Block>,
+    current: Option<BlockKeys<'a>>,
+    remaining: usize,
+}
+
+impl<'a> Iterator for Iter<'a> {
+    type Item = InlinableString;
+
+    fn next(&mut self) -> Option<InlinableString> {
+        loop {
+            if let Some(ref mut keys) = self.current {
+                if let Some(key) = keys.next_key() {
+                    self.remaining -= 1;
+                    return Some(InlinableString::from(key));
+                }
+            }
+            self.current = Some(self.blocks.next()?.keys());
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.remaining, Some(self.remaining))
+    }
+}
+
+impl<'a> ExactSizeIterator for Iter<'a> {}
+
+impl<'a> fmt::Debug for Iter<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Iter")
+            .field("remaining", &self.remaining)
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{InlineStringSet, MAX_BLOCK_LEN};
+    use alloc::collections::BTreeSet;
+    use alloc::format;
+    use alloc::string::String;
+    use alloc::vec::Vec;
+
+    #[test]
+    fn test_matches_btreeset() {
+        let mut set = InlineStringSet::new();
+        let mut model = BTreeSet::new();
+
+        let mut seed = 0x9e37_79b9u32;
+        for _ in 0..2000 {
+            seed ^= seed << 13;
+            seed ^= seed >> 17;
+            seed ^= seed << 5;
+            let key = format!("ns::mod{}::item{}", seed % 7, seed % 97);
+            if seed % 3 == 0 {
+                assert_eq!(set.remove(&key), model.remove(&key));
+            } else {
+                assert_eq!(set.insert(&key), model.insert(key.clone()));
+            }
+            assert_eq!(set.len(), model.len());
+        }
+
+        let keys: Vec<String> = set.iter().map(|k| String::from(&k[..])).collect();
+        let expected: Vec<String> = model.iter().cloned().collect();
+        assert_eq!(keys, expected);
+        assert!(set.blocks.iter().all(|b| b.len() <= MAX_BLOCK_LEN));
+        for key in &model {
+            assert!(set.contains(key));
+        }
+        assert!(!set.contains("ns::"));
+    }
+
+    #[test]
+    fn test_shared_prefix_char_boundary() {
+        let set: InlineStringSet = ["è", "é", "éa", ""].iter().cloned().collect();
+        let keys: Vec<_> = set.iter().collect();
+        assert_eq!(keys, ["", "è", "é", "éa"]);
+        assert!(set.contains("é"));
+        assert!(!set.contains("ê"));
+    }
+
+    #[test]
+    fn test_remove_all() {
+        let mut set: InlineStringSet = (0..40)
+            .map(|i| format!("key{:02}", i))
+            .collect::<Vec<_>>()
+            .iter()
+            .map(|s| &s[..])
+            .collect();
+        assert_eq!(set.len(), 40);
+        for i in 0..40 {
+            assert!(set.remove(&format!("key{:02}", i)));
+        }
+        assert!(set.is_empty());
+        assert!(set.blocks.is_empty());
+        assert_eq!(set.iter().count(), 0);
+    }
+
+    #[test]
+    fn test_eq_ignores_block_layout() {
+        let keys: Vec<String> = (0..40).map(|i| format!("key{:02}", i)).collect();
+        let forward: InlineStringSet = keys.iter().map(|s| &s[..]).collect();
+        let backward: InlineStringSet = keys.iter().rev().map(|s| &s[..]).collect();
+        assert_eq!(forward, backward);
+        assert_eq!(format!("{:?}", forward), format!("{:?}", backward));
+    }
+}
@@ -0,0 +1,240 @@
+// Copyright 2015, The inlinable_string crate Developers. See the COPYRIGHT file
+// at the top-level directory of this distribution.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license <LICENSE-MIT
+// or http://opensource.org/licenses/MIT>, at your option. This file may not be
+// copied, modified, or distributed except according to those terms.
+
+//! The `SpareCapacity` guard returned by `spare_capacity_mut`.
+//!
+//! A `SpareCapacity` dereferences to a slice of possibly uninitialized bytes,
+//! as `Vec::spare_capacity_mut` does, as long as the string's remaining
+//! capacity. Write into it, then call `commit` with the number of bytes
+//! written to append them to the string. The bytes are checked to be valid
+//! UTF-8 before the string's length changes, so a string can never be left
+//! holding invalid UTF-8, even if the guard is leaked.
+//!
+//! For a heap-allocated string the slice is the string's own spare capacity,
+//! and nothing is initialized or copied up front. An inline string must keep
+//! the bytes past its length zeroed, so it hands out a scratch buffer instead,
+//! and `commit` copies the written bytes into the string.
+//!
+//! # Examples
+//!
+//! ```
+//! use inlinable_string::{InlinableString, StringExt};
+//!
+//! let mut s = InlinableString::from("id=");
+//! let mut spare = s.spare_capacity_mut();
+//! for (dst, &byte) in spare.iter_mut().zip(b"042") {
+//!     dst.write(byte);
+//! }
+//! // Safe because the first three bytes were just written.
+//! unsafe { spare.commit(3).unwrap() };
+//! assert_eq!(s, "id=042");
+//! ```
+
+use crate::InlineString;
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::fmt;
+use core::mem::MaybeUninit;
+use core::ops;
+use core::slice;
+use core::str::{self, Utf8Error};
+
+/// A writable view of a string's spare capacity.
+///
+/// Created by `InlineString::spare_capacity_mut` and
+/// `InlinableString::spare_capacity_mut`. See the
+/// [module level documentation](./index.html) for more.
+pub struct SpareCapacity<'a> {
+    inner: Inner<'a>,
+}
+
+enum Inner<'a> {
+    /// Bytes are written into a scratch buffer and only copied into the
+    /// string on commit, so the string's zeroed tail is never disturbed.
+    Inline {
+        string: &'a mut InlineString,
+        buf: [MaybeUninit<u8>; InlineString::CAPACITY],
+        len: usize,
+    },
+    /// The string's buffer. Only validated UTF-8 is ever brought within its
+    /// length.
+    Heap(&'a mut Vec<u8>),
+}
+
+impl<'a> SpareCapacity<'a> {
+    pub(crate) fn inline(string: &'a mut InlineString) -> SpareCapacity<'a> {
+        let len = InlineString::CAPACITY - string.len();
+        SpareCapacity {
+            inner: Inner::Inline {
+                string,
+                buf: [MaybeUninit::uninit(); InlineString::CAPACITY],
+                len,
+            },
+        }
+    }
+
+    pub(crate) fn heap(string: &'a mut String) -> SpareCapacity<'a> {
+        // Safe because nothing is brought within the vector's length without
+        // first being checked to be valid UTF-8.
+        let vec = unsafe { string.as_mut_vec() };
+        SpareCapacity {
+            inner: Inner::Heap(vec),
+        }
+    }
+
+    /// Appends the first `n` bytes of the spare capacity to the string,
+    /// consuming the guard.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error, and leaves the string unchanged, if those bytes are
+    /// not valid UTF-8.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `n` is greater than the length of the spare capacity.
+    ///
+    /// # Safety
+    ///
+    /// The first `n` bytes of the spare capacity must have been initialized.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use inlinable_string::InlineString;
+    ///
+    /// let mut s = InlineString::from("a");
+    /// let mut spare = s.spare_capacity_mut();
+    /// spare[0].write(0xff);
+    /// assert!(unsafe { spare.commit(1) }.is_err());
+    /// assert_eq!(s, "a");
+    /// ```
+    pub unsafe fn commit(self, n: usize) -> Result<(), Utf8Error> {
+        assert!(
+            n <= self.len(),
+            "inlinable_string: committed more bytes than the spare capacity"
+        );
+        match self.inner {
+            Inner::Inline { string, buf, .. } => {
+                let written = str::from_utf8(slice::from_raw_parts(buf.as_ptr() as *const u8, n))?;
+                string
+                    .push_str(written)
+                    .expect("inlinable_string: spare capacity overflowed inline storage");
+            }
+            Inner::Heap(vec) => {
+                let len = vec.len();
+                str::from_utf8(slice::from_raw_parts(vec.as_ptr().add(len), n))?;
+                vec.set_len(len + n);
+            }
+        }
+        Ok(())
+    }
+}
+
+impl<'a> ops::Deref for SpareCapacity<'a> {
+    type Target = [MaybeUninit<u8>];
+
+    #[inline]
+    fn deref(&self) -> &[MaybeUninit<u8>] {
+        match self.inner {
+            Inner::Inline { ref buf, len, .. } => &buf[..len],
+            Inner::Heap(ref vec) => unsafe {
+                slice::from_raw_parts(
+                    vec.as_ptr().add(vec.len()) as *const MaybeUninit<u8>,
+                    vec.capacity() - vec.len(),
+                )
+            },
+        }
+    }
+}
+
+impl<'a> ops::DerefMut for SpareCapacity<'a> {
+    #[inline]
+    fn deref_mut(&mut self) -> &mut [MaybeUninit<u8>] {
+        match self.inner {
+            Inner::Inline {
+                ref mut buf, len, ..
+            } => &mut buf[..len],
+            Inner::Heap(ref mut vec) => vec.spare_capacity_mut(),
+        }
+    }
+}
+
+impl<'a> fmt::Debug for SpareCapacity<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("SpareCapacity")
+            .field("len", &self.len())
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::SpareCapacity;
+    use crate::{InlinableString, InlineString, StringExt};
+
+    fn write(spare: &mut SpareCapacity<'_>, bytes: &[u8]) {
+        for (dst, &byte) in spare.iter_mut().zip(bytes) {
+            dst.write(byte);
+        }
+    }
+
+    #[test]
+    fn test_spare_inline() {
+        let mut s = InlineString::from("ab");
+        {
+            let mut spare = s.spare_capacity_mut();
+            assert_eq!(spare.len(), InlineString::CAPACITY - 2);
+            write(&mut spare, "é".as_bytes());
+            // Dropping the guard without committing leaves the string alone.
+        }
+        assert_eq!(s, "ab");
+        assert_eq!(s.clone().into_bytes()[2..], [0; InlineString::CAPACITY - 2]);
+
+        let mut spare = s.spare_capacity_mut();
+        write(&mut spare, "é".as_bytes());
+        unsafe { spare.commit(2).unwrap() };
+        assert_eq!(s, "abé");
+
+        // Half of a two-byte character.
+        let mut spare = s.spare_capacity_mut();
+        write(&mut spare, &"é".as_bytes()[..1]);
+        assert!(unsafe { spare.commit(1) }.is_err());
+        assert_eq!(s, "abé");
+    }
+
+    #[test]
+    fn test_spare_heap() {
+        let mut s = InlinableString::with_capacity(100);
+        s.push_str("abc");
+        let ptr = s.as_ptr();
+        let mut spare = s.spare_capacity_mut();
+        assert!(spare.len() >= 97);
+        // The guard hands out the string's own buffer.
+        assert_eq!(spare.as_ptr() as *const u8, unsafe { ptr.add(3) });
+        write(&mut spare, b"defg");
+        unsafe { spare.commit(4).unwrap() };
+        assert_eq!(s, "abcdefg");
+
+        let mut spare = s.spare_capacity_mut();
+        write(&mut spare, b"h\xff");
+        assert!(unsafe { spare.commit(2) }.is_err());
+        assert_eq!(s, "abcdefg");
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_commit_too_much() {
+        let mut s = InlinableString::new();
+        unsafe {
+            s.spare_capacity_mut()
+                .commit(InlineString::CAPACITY + 1)
+                .unwrap();
+        }
+    }
+}
@@ -0,0 +1,65 @@
+// Copyright 2015, The inlinable_string crate Developers. See the COPYRIGHT file
+// at the top-level directory of this distribution.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license <LICENSE-MIT
+// or http://opensource.org/licenses/MIT>, at your option. This file may not be
+// copied, modified, or distributed except according to those terms.
+
+//! Trait implementations for types that only exist in `std`.
+
+use crate::InlinableString;
+use std::convert::TryFrom;
+use std::ffi::OsStr;
+use std::path::Path;
+use std::str::Utf8Error;
+
+/// Checks that the OS string is valid UTF-8, storing it inline when it fits.
+impl<'a> TryFrom<&'a OsStr> for InlinableString {
+    type Error = Utf8Error;
+
+    #[inline]
+    fn try_from(string: &'a OsStr) -> Result<InlinableString, Utf8Error> {
+        <&str>::try_from(string).map(InlinableString::from)
+    }
+}
+
+/// Checks that the path is valid UTF-8, storing it inline when it fits.
+impl<'a> TryFrom<&'a Path> for InlinableString {
+    type Error = Utf8Error;
+
+    #[inline]
+    fn try_from(path: &'a Path) -> Result<InlinableString, Utf8Error> {
+        InlinableString::try_from(path.as_os_str())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::InlinableString;
+    use std::convert::TryFrom;
+    use std::ffi::OsStr;
+    use std::path::Path;
+
+    #[test]
+    fn test_try_from_os_str() {
+        let s = InlinableString::try_from(OsStr::new("HOME")).unwrap();
+        assert_eq!(s, "HOME");
+        assert!(s.is_inline());
+
+        let s = InlinableString::try_from(Path::new("/usr/share/doc/inlinable_string/README.md"))
+            .unwrap();
+        assert_eq!(s, "/usr/share/doc/inlinable_string/README.md");
+        assert!(s.is_heap());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_try_from_invalid_os_str() {
+        use std::os::unix::ffi::OsStrExt;
+
+        let invalid = OsStr::from_bytes(b"caf\xe9");
+        assert!(InlinableString::try_from(invalid).is_err());
+        assert!(InlinableString::try_from(Path::new(invalid)).is_err());
+    }
+}
@@ -0,0 +1,258 @@
+// Copyright 2015, The inlinable_string crate Developers. See the COPYRIGHT file
+// at the top-level directory of this distribution.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license <LICENSE-MIT
+// or http://opensource.org/licenses/MIT>, at your option. This file may not be
+// copied, modified, or distributed except according to those terms.
+
+//! [`proptest`](https://docs.rs/proptest) strategies for `InlinableString`
+//! and `InlineString`.
+//!
+//! Both types implement `proptest::arbitrary::Arbitrary`, so `any::<T>()`
+//! works, and the strategies here generate strings with chosen contents and
+//! lengths. Every `InlinableString` strategy stores short strings inline or
+//! on the heap at random, so properties are checked against both variants.
+//!
+//! This module requires the `proptest` feature.
+//!
+//! # Examples
+//!
+//! ```
+//! use inlinable_string::strategy;
+//! use inlinable_string::{InlinableString, InlineString, StringExt};
+//! use proptest::prelude::*;
+//!
+//! proptest! {
+//!     fn push_str_appends(
+//!         mut s in strategy::around_capacity(),
+//!         t in any::<InlineString>(),
+//!     ) {
+//!         let len = s.len();
+//!         s.push_str(&t);
+//!         prop_assert_eq!(&s[len..], &*t);
+//!     }
+//! }
+//! # push_str_appends();
+//! ```
+
+use crate::{InlinableString, InlineString};
+use alloc::string::String;
+use core::convert::TryFrom;
+use proptest::arbitrary::{any, any_with, Arbitrary};
+use proptest::collection::{self, SizeRange};
+use proptest::prop_oneof;
+use proptest::strategy::{BoxedStrategy, Strategy};
+use proptest::string::{string_regex, StringParam};
+
+impl Arbitrary for InlinableString {
+    type Parameters = StringParam;
+    type Strategy = BoxedStrategy<InlinableString>;
+
+    fn arbitrary_with(args: StringParam) -> BoxedStrategy<InlinableString> {
+        with_random_storage(any_with::<String>(args))
+    }
+}
+
+/// Keeps as much of an arbitrary `String` as fits inline, cut at a character
+/// boundary.
+impl Arbitrary for InlineString {
+    type Parameters = StringParam;
+    type Strategy = BoxedStrategy<InlineString>;
+
+    fn arbitrary_with(args: StringParam) -> BoxedStrategy<InlineString> {
+        any_with::<String>(args)
+            .prop_map(|contents| {
+                let mut string = InlineString::new();
+                string.push_str_truncating(&contents);
+                string
+            })
+            .boxed()
+    }
+}
+
+/// Returns a strategy for strings matching the regular expression `regex`.
+///
+/// Bounded repetitions in `regex` control the length, so a pattern such as
+/// `"[a-z]{20,40}"` straddles `InlineString::CAPACITY`.
+///
+/// # Panics
+///
+/// Panics if `regex` is not a regular expression that `proptest` can
+/// generate strings from, like using a `&str` directly as a strategy does.
+///
+/// # Examples
+///
+/// ```
+/// use inlinable_string::strategy;
+/// use proptest::strategy::{Strategy, ValueTree};
+/// use proptest::test_runner::TestRunner;
+///
+/// let words = strategy::matching("[a-z]{1,8}( [a-z]{1,8}){0,5}");
+/// let s = words.new_tree(&mut TestRunner::default()).unwrap().current();
+/// assert!(s.split(' ').all(|word| (1..=8).contains(&word.len())));
+/// ```
+pub fn matching(regex: &str) -> BoxedStrategy<InlinableString> {
+    with_random_storage(regex_strategy(regex))
+}
+
+/// Returns a strategy for strings matching the regular expression `regex`
+/// that fit in an `InlineString`. Matches longer than
+/// `InlineString::CAPACITY` bytes are rejected.
+///
+/// # Panics
+///
+/// Panics if `regex` is not a regular expression that `proptest` can
+/// generate strings from, like using a `&str` directly as a strategy does.
+///
+/// # Examples
+///
+/// ```
+/// use inlinable_string::strategy;
+/// use proptest::strategy::{Strategy, ValueTree};
+/// use proptest::test_runner::TestRunner;
+///
+/// let ids = strategy::inline_matching("[0-9a-f]{8}");
+/// let id = ids.new_tree(&mut TestRunner::default()).unwrap().current();
+/// assert_eq!(id.len(), 8);
+/// ```
+pub fn inline_matching(regex: &str) -> BoxedStrategy<InlineString> {
+    regex_strategy(regex)
+        .prop_filter_map("too long to store inline", |contents| {
+            InlineString::try_from(contents).ok()
+        })
+        .boxed()
+}
+
+/// Returns a strategy for strings of characters drawn from `chars`, with a
+/// length in characters drawn from `len`.
+///
+/// # Examples
+///
+/// ```
+/// use inlinable_string::{strategy, InlineString};
+/// use proptest::char;
+/// use proptest::strategy::{Strategy, ValueTree};
+/// use proptest::test_runner::TestRunner;
+///
+/// let cap = InlineString::CAPACITY;
+/// let strings = strategy::with_len(char::range('a', 'z'), cap - 2..cap + 2);
+/// let s = strings.new_tree(&mut TestRunner::default()).unwrap().current();
+/// assert!(s.len() >= cap - 2 && s.len() < cap + 2);
+/// ```
+pub fn with_len<S>(chars: S, len: impl Into<SizeRange>) -> BoxedStrategy<InlinableString>
+where
+    S: Strategy<Value = char> + 'static,
+{
+    with_random_storage(collection::vec(chars, len).prop_map(|chars| chars.into_iter().collect()))
+}
+
+/// Returns a strategy for strings within four characters of
+/// `InlineString::CAPACITY` in length, so that they land on both sides of the
+/// inline/heap boundary.
+///
+/// Most characters are printable ASCII, with the occasional arbitrary
+/// character to cover multi-byte characters that straddle the boundary.
+///
+/// # Examples
+///
+/// ```
+/// use inlinable_string::{strategy, InlineString};
+/// use proptest::strategy::{Strategy, ValueTree};
+/// use proptest::test_runner::TestRunner;
+///
+/// let strings = strategy::around_capacity();
+/// let s = strings.new_tree(&mut TestRunner::default()).unwrap().current();
+/// let chars = s.chars().count();
+/// assert!((InlineString::CAPACITY - 4..=InlineString::CAPACITY + 4).contains(&chars));
+/// ```
+pub fn around_capacity() -> BoxedStrategy<InlinableString> {
+    let chars = prop_oneof![
+        9 => proptest::char::range(' ', '~'),
+        1 => any::<char>(),
+    ];
+    with_len(
+        chars,
+        InlineString::CAPACITY - 4..=InlineString::CAPACITY + 4,
+    )
+}
+
+fn regex_strategy(regex: &str) -> impl Strategy<Value = String> {
+    match string_regex(regex) {
+        Ok(strategy) => strategy,
+        Err(e) => panic!("inlinable_string: invalid regex {:?}: {}", regex, e),
+    }
+}
+
+/// Stores each generated string inline or on the heap at random. A string
+/// too long to store inline always goes on the heap.
+fn with_random_storage<S>(contents: S) -> BoxedStrategy<InlinableString>
+where
+    S: Strategy<Value = String> + 'static,
+{
+    (contents, any::<bool>())
+        .prop_map(|(contents, heap)| {
+            if heap {
+                InlinableString::Heap(contents)
+            } else {
+                InlinableString::from(contents)
+            }
+        })
+        .boxed()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{around_capacity, inline_matching, matching, with_len};
+    use crate::{InlinableString, InlineString, StringExt};
+    use proptest::prelude::*;
+
+    proptest! {
+        #[test]
+        fn test_arbitrary(s in any::<InlinableString>(), t in any::<InlineString>()) {
+            prop_assert!(s.is_heap() || s.len() <= InlineString::CAPACITY);
+            prop_assert!(t.len() <= InlineString::CAPACITY);
+        }
+
+        #[test]
+        fn test_matching(s in matching("[a-c]{25,35}")) {
+            prop_assert!((25..=35).contains(&s.len()));
+            prop_assert!(s.bytes().all(|b| (b'a'..=b'c').contains(&b)));
+        }
+
+        #[test]
+        fn test_inline_matching(s in inline_matching("x{0,40}")) {
+            prop_assert!(s.len() <= InlineString::CAPACITY);
+            prop_assert!(s.bytes().all(|b| b == b'x'));
+        }
+
+        #[test]
+        fn test_with_len(s in with_len(proptest::char::range('α', 'ω'), 3..6)) {
+            prop_assert!((3..6).contains(&s.chars().count()));
+        }
+    }
+
+    #[test]
+    fn test_around_capacity_covers_both_variants() {
+        use proptest::strategy::ValueTree;
+        use proptest::test_runner::TestRunner;
+
+        let strategy = around_capacity();
+        let mut runner = TestRunner::deterministic();
+        let (mut inline, mut heap) = (false, false);
+        for _ in 0..100 {
+            let s = strategy.new_tree(&mut runner).unwrap().current();
+            let chars = s.chars().count();
+            assert!((InlineString::CAPACITY - 4..=InlineString::CAPACITY + 4).contains(&chars));
+            inline |= s.is_inline();
+            heap |= s.is_heap() && s.len() > InlineString::CAPACITY;
+        }
+        assert!(inline && heap);
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_invalid_regex() {
+        let _ = matching("(");
+    }
+}
@@ -12,15 +12,45 @@
 //! See the [crate level documentation](./../index.html) for more.
 
 use alloc::borrow::{Borrow, Cow};
+use alloc::boxed::Box;
+use alloc::collections::TryReserveError;
 use alloc::vec::Vec;
 use alloc::string::{String, FromUtf16Error, FromUtf8Error};
 use core::cmp::PartialEq;
 use core::fmt::Display;
+use core::ops::RangeBounds;
+use crate::drain::{self, Drain};
+use crate::InlinableString;
 
 /// A trait that exists to abstract string operations over any number of
 /// concrete string type implementations.
 ///
 /// See the [crate level documentation](./../index.html) for more.
+///
+/// # Trait Objects
+///
+/// `StringExt` is object safe, so `&mut dyn StringExt` can stand in for any
+/// implementing string. Constructors and methods that are generic or take or
+/// return `Self` by value require `Self: Sized`, so they are not available on
+/// trait objects; everything else, such as `push_str`, `insert_str`,
+/// `truncate` and `len`, is.
+///
+/// ```
+/// use inlinable_string::{InlinableString, StringExt};
+///
+/// fn quote(strings: &mut [&mut dyn StringExt]) {
+///     for s in strings {
+///         s.insert_str(0, "> ");
+///         s.push('!');
+///     }
+/// }
+///
+/// let mut a = String::from("hi");
+/// let mut b = InlinableString::from("hey");
+/// quote(&mut [&mut a, &mut b]);
+/// assert_eq!(a, "> hi!");
+/// assert_eq!(b, "> hey!");
+/// ```
 pub trait StringExt<'a>:
     Borrow<str>
     + Display
@@ -44,8 +74,8 @@ pub trait StringExt<'a>:
 
     /// Creates a new string buffer with the given capacity. The string will be
     /// able to hold at least `capacity` bytes without reallocating. If
-    /// `capacity` is less than or equal to `INLINE_STRING_CAPACITY`, the string
-    /// will not heap allocate.
+    /// `capacity` is less than or equal to `InlinableString::INLINE_CAPACITY`,
+    /// the string will not heap allocate.
     ///
     /// # Examples
     ///
@@ -185,6 +215,29 @@ pub trait StringExt<'a>:
     /// ```
     fn into_bytes(self) -> Vec<u8>;
 
+    /// Converts this string into a `Box<str>`, dropping any excess capacity.
+    ///
+    /// The default implementation goes through `into_bytes`; implementors
+    /// that own a `String` should override it to move their buffer.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use inlinable_string::{InlinableString, StringExt};
+    ///
+    /// let s = InlinableString::from("hello");
+    /// let boxed: Box<str> = s.into_boxed_str();
+    /// assert_eq!(&*boxed, "hello");
+    /// ```
+    fn into_boxed_str(self) -> Box<str>
+    where
+        Self: Sized,
+    {
+        String::from_utf8(self.into_bytes())
+            .expect("StringExt::into_bytes returned invalid UTF-8")
+            .into_boxed_str()
+    }
+
     /// Pushes the given string onto this string buffer.
     ///
     /// # Examples
@@ -253,9 +306,56 @@ pub trait StringExt<'a>:
     /// ```
     fn reserve_exact(&mut self, additional: usize);
 
+    /// Tries to reserve capacity for at least `additional` more bytes to be
+    /// inserted in the given `InlinableString`, returning an error instead of
+    /// aborting if the capacity overflows or the allocator reports a failure.
+    ///
+    /// The default implementation calls `reserve` and so cannot report
+    /// failure; implementors that can should override it.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use inlinable_string::{InlinableString, StringExt};
+    ///
+    /// let mut s = InlinableString::new();
+    /// s.try_reserve(100).unwrap();
+    /// assert!(s.capacity() >= 100);
+    /// assert!(s.try_reserve(usize::MAX).is_err());
+    /// ```
+    #[inline]
+    fn try_reserve(&mut self, additional: usize) -> Result<(), TryReserveError> {
+        self.reserve(additional);
+        Ok(())
+    }
+
+    /// Tries to reserve the minimum capacity for exactly `additional` more
+    /// bytes to be inserted in the given `InlinableString`, returning an error
+    /// instead of aborting if the capacity overflows or the allocator reports
+    /// a failure.
+    ///
+    /// The default implementation calls `reserve_exact` and so cannot report
+    /// failure; implementors that can should override it.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use inlinable_string::{InlinableString, StringExt};
+    ///
+    /// let mut s = InlinableString::new();
+    /// s.try_reserve_exact(100).unwrap();
+    /// assert!(s.capacity() >= 100);
+    /// assert!(s.try_reserve_exact(usize::MAX).is_err());
+    /// ```
+    #[inline]
+    fn try_reserve_exact(&mut self, additional: usize) -> Result<(), TryReserveError> {
+        self.reserve_exact(additional);
+        Ok(())
+    }
+
     /// Shrinks the capacity of this string buffer to match its length. If the
-    /// string's length is less than `INLINE_STRING_CAPACITY` and the string is
-    /// heap-allocated, then it is demoted to inline storage.
+    /// string's length is less than `InlinableString::INLINE_CAPACITY` and the
+    /// string is heap-allocated, then it is demoted to inline storage.
     ///
     /// # Examples
     ///
@@ -266,10 +366,35 @@ pub trait StringExt<'a>:
     /// s.reserve(100);
     /// assert!(s.capacity() >= 100);
     /// s.shrink_to_fit();
-    /// assert_eq!(s.capacity(), inlinable_string::INLINE_STRING_CAPACITY);
+    /// assert_eq!(s.capacity(), InlinableString::INLINE_CAPACITY);
     /// ```
     fn shrink_to_fit(&mut self);
 
+    /// Shrinks the capacity of this string buffer with a lower bound: the
+    /// capacity will remain at least as large as both the length and
+    /// `min_capacity`. If both fit within `InlinableString::INLINE_CAPACITY`
+    /// and the string is heap-allocated, then it is demoted to inline storage.
+    ///
+    /// Capacity is only a hint, so the default implementation does nothing;
+    /// implementors that can release memory should override it.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use inlinable_string::{InlinableString, StringExt};
+    ///
+    /// let mut s = InlinableString::from("foo");
+    /// s.reserve(100);
+    /// s.shrink_to(50);
+    /// assert!(s.capacity() >= 50);
+    /// s.shrink_to(10);
+    /// assert_eq!(s.capacity(), InlinableString::INLINE_CAPACITY);
+    /// ```
+    #[inline]
+    fn shrink_to(&mut self, min_capacity: usize) {
+        let _ = min_capacity;
+    }
+
     /// Adds the given character to the end of the string.
     ///
     /// # Examples
@@ -468,6 +593,432 @@ pub trait StringExt<'a>:
     fn clear(&mut self) {
         self.truncate(0);
     }
+
+    /// Removes the given byte range from the string and returns an iterator
+    /// over the removed characters.
+    ///
+    /// The range is removed even if the iterator is dropped before it is
+    /// fully consumed.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the start or end of the range does not lie on a character
+    /// boundary, or if either is out of bounds.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use inlinable_string::{InlinableString, StringExt};
+    ///
+    /// let mut s = InlinableString::from("hello world");
+    /// let hello: String = s.drain(..6).collect();
+    /// assert_eq!(hello, "hello ");
+    /// assert_eq!(s, "world");
+    /// ```
+    fn drain<R>(&mut self, range: R) -> Drain<'_>
+    where
+        R: RangeBounds<usize>,
+        Self: Sized,
+    {
+        let (start, removed, tail) = {
+            let string: &str = Borrow::borrow(&*self);
+            let range = drain::resolve_range(string, range);
+            (
+                range.start,
+                InlinableString::from(&string[range.clone()]),
+                InlinableString::from(&string[range.end..]),
+            )
+        };
+        self.truncate(start);
+        self.push_str(&tail);
+        Drain::owned(removed)
+    }
+
+    /// Copies the characters in the byte range `src` to the end of the
+    /// string.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the start or end of the range does not lie on a character
+    /// boundary, or if either is out of bounds.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use inlinable_string::{InlinableString, StringExt};
+    ///
+    /// let mut s = InlinableString::from("abcde");
+    /// s.extend_from_within(2..);
+    /// assert_eq!(s, "abcdecde");
+    /// s.extend_from_within(..2);
+    /// assert_eq!(s, "abcdecdeab");
+    /// ```
+    fn extend_from_within<R>(&mut self, src: R)
+    where
+        R: RangeBounds<usize>,
+        Self: Sized,
+    {
+        let copy = {
+            let string: &str = Borrow::borrow(&*self);
+            let src = drain::resolve_range(string, src);
+            InlinableString::from(&string[src])
+        };
+        self.push_str(&copy);
+    }
+
+    /// Returns the character that starts at byte index `byte_idx`, or `None`
+    /// if `byte_idx` is out of bounds or not on a character boundary.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use inlinable_string::{InlinableString, StringExt};
+    ///
+    /// let s = InlinableString::from("añb");
+    /// assert_eq!(s.char_at(1), Some('ñ'));
+    /// assert_eq!(s.char_at(2), None);
+    /// assert_eq!(s.char_at(3), Some('b'));
+    /// assert_eq!(s.char_at(4), None);
+    /// ```
+    #[inline]
+    fn char_at(&self, byte_idx: usize) -> Option<char> {
+        let string: &str = self.borrow();
+        string.get(byte_idx..)?.chars().next()
+    }
+
+    /// Returns the `n`th character of this string (counting from zero), or
+    /// `None` if the string has `n` or fewer characters.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use inlinable_string::{InlinableString, StringExt};
+    ///
+    /// let s = InlinableString::from("añb");
+    /// assert_eq!(s.nth_char(1), Some('ñ'));
+    /// assert_eq!(s.nth_char(2), Some('b'));
+    /// assert_eq!(s.nth_char(3), None);
+    /// ```
+    #[inline]
+    fn nth_char(&self, n: usize) -> Option<char> {
+        let string: &str = self.borrow();
+        string.chars().nth(n)
+    }
+
+    /// Shortens this string to its first `n` characters. Does nothing if the
+    /// string has `n` or fewer characters.
+    ///
+    /// Unlike `truncate`, which takes a byte length, this never panics.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use inlinable_string::{InlinableString, StringExt};
+    ///
+    /// let mut s = InlinableString::from("añb€c");
+    /// s.truncate_chars(4);
+    /// assert_eq!(s, "añb€");
+    /// s.truncate_chars(10);
+    /// assert_eq!(s, "añb€");
+    /// ```
+    #[inline]
+    fn truncate_chars(&mut self, n: usize) {
+        let string: &str = (*self).borrow();
+        if let Some((idx, _)) = string.char_indices().nth(n) {
+            self.truncate(idx);
+        }
+    }
+
+    /// Appends each of the given string slices onto the end of this string.
+    ///
+    /// The combined length is reserved up front, so the string grows (or is
+    /// promoted out of inline storage) at most once.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use inlinable_string::{InlinableString, StringExt};
+    ///
+    /// let mut s = InlinableString::from("GET ");
+    /// s.extend_from_slices(&["/index.html", " ", "HTTP/1.1"]);
+    /// assert_eq!(s, "GET /index.html HTTP/1.1");
+    /// ```
+    fn extend_from_slices(&mut self, parts: &[&str]) {
+        let additional = parts.iter().map(|part| part.len()).sum();
+        self.reserve(additional);
+        for part in parts {
+            self.push_str(part);
+        }
+    }
+
+    /// Returns the number of non-overlapping occurrences of `pattern` in this
+    /// string.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use inlinable_string::{InlinableString, StringExt};
+    ///
+    /// let s = InlinableString::from("abababa");
+    /// assert_eq!(s.count_matches("aba"), 2);
+    /// assert_eq!(s.count_matches("c"), 0);
+    /// ```
+    fn count_matches(&self, pattern: &str) -> usize {
+        let string: &str = self.borrow();
+        string.matches(pattern).count()
+    }
+
+    /// Returns true if this string contains any of the given `patterns`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use inlinable_string::{InlinableString, StringExt};
+    ///
+    /// let s = InlinableString::from("drop table");
+    /// assert!(s.contains_any(&["insert", "drop"]));
+    /// assert!(!s.contains_any(&["insert", "delete"]));
+    /// assert!(!s.contains_any(&[]));
+    /// ```
+    fn contains_any(&self, patterns: &[&str]) -> bool {
+        let string: &str = self.borrow();
+        patterns.iter().any(|pattern| string.contains(pattern))
+    }
+
+    /// Passes this string's contents to `f` and, if `f` returns
+    /// `Cow::Owned`, replaces the contents with the result. A `Cow::Borrowed`
+    /// result leaves the string (and its storage) untouched. Returns whether
+    /// the string was replaced.
+    ///
+    /// The replacement reuses the existing buffer where it is large enough.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use inlinable_string::{InlinableString, StringExt};
+    /// use std::borrow::Cow;
+    ///
+    /// fn strip_cr(s: &str) -> Cow<str> {
+    ///     if s.contains('\r') {
+    ///         Cow::Owned(s.replace('\r', ""))
+    ///     } else {
+    ///         Cow::Borrowed(s)
+    ///     }
+    /// }
+    ///
+    /// let mut s = InlinableString::from("a\r\nb");
+    /// assert!(s.apply(strip_cr));
+    /// assert_eq!(s, "a\nb");
+    /// assert!(!s.apply(strip_cr));
+    /// ```
+    fn apply<F>(&mut self, f: F) -> bool
+    where
+        F: FnOnce(&str) -> Cow<'_, str>,
+        Self: Sized,
+    {
+        let replacement = {
+            let string: &str = Borrow::borrow(&*self);
+            match f(string) {
+                Cow::Borrowed(_) => return false,
+                Cow::Owned(replacement) => replacement,
+            }
+        };
+        self.clear();
+        self.push_str(&replacement);
+        true
+    }
+
+    /// Replaces every tab character with enough spaces to advance to the next
+    /// multiple of `tab_stop` columns. Columns are counted in characters and
+    /// reset after each `\n` or `\r`. A `tab_stop` of zero removes tabs.
+    ///
+    /// The expanded length is computed up front, so the string is rebuilt in a
+    /// single pass with at most one allocation.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use inlinable_string::{InlinableString, StringExt};
+    ///
+    /// let mut s = InlinableString::from("a\tbc\td");
+    /// s.expand_tabs(4);
+    /// assert_eq!(s, "a   bc  d");
+    /// ```
+    fn expand_tabs(&mut self, tab_stop: usize)
+    where
+        Self: Sized,
+    {
+        let tab_width = |column: usize| {
+            if tab_stop == 0 {
+                0
+            } else {
+                tab_stop - column % tab_stop
+            }
+        };
+
+        let expanded = {
+            let string: &str = Borrow::borrow(&*self);
+            if !string.contains('\t') {
+                return;
+            }
+
+            let mut expanded_len = 0;
+            let mut column = 0;
+            for ch in string.chars() {
+                match ch {
+                    '\t' => {
+                        let spaces = tab_width(column);
+                        expanded_len += spaces;
+                        column += spaces;
+                    }
+                    '\n' | '\r' => {
+                        expanded_len += 1;
+                        column = 0;
+                    }
+                    _ => {
+                        expanded_len += ch.len_utf8();
+                        column += 1;
+                    }
+                }
+            }
+
+            let mut expanded = Self::with_capacity(expanded_len);
+            let mut column = 0;
+            for ch in string.chars() {
+                match ch {
+                    '\t' => {
+                        let spaces = tab_width(column);
+                        for _ in 0..spaces {
+                            expanded.push(' ');
+                        }
+                        column += spaces;
+                    }
+                    '\n' | '\r' => {
+                        expanded.push(ch);
+                        column = 0;
+                    }
+                    _ => {
+                        expanded.push(ch);
+                        column += 1;
+                    }
+                }
+            }
+            expanded
+        };
+        *self = expanded;
+    }
+
+    /// Returns this string repeated `n` times.
+    ///
+    /// The result's length is computed up front, so an `InlinableString`
+    /// result is stored inline whenever it fits. This is not called `repeat`
+    /// so that `s.repeat(n)` still reaches `str::repeat` and returns a
+    /// `String`, whether or not this trait is in scope.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the result's length would overflow `usize`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use inlinable_string::{InlinableString, StringExt};
+    ///
+    /// let s = InlinableString::from("ab");
+    /// let repeated = s.repeat_inlinable(3);
+    /// assert_eq!(repeated, "ababab");
+    /// assert!(repeated.is_inline());
+    /// ```
+    fn repeat_inlinable(&self, n: usize) -> Self
+    where
+        Self: Sized,
+    {
+        let string: &str = self.borrow();
+        let len = string
+            .len()
+            .checked_mul(n)
+            .expect("inlinable_string: repeat length overflows usize");
+        let mut result = Self::with_capacity(len);
+        for _ in 0..n {
+            result.push_str(string);
+        }
+        result
+    }
+
+    /// Returns a copy of this string with its first character converted to
+    /// uppercase and the rest left untouched.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use inlinable_string::{InlinableString, StringExt};
+    ///
+    /// let s = InlinableString::from("élan vital");
+    /// assert_eq!(s.capitalize_ext(), "Élan vital");
+    /// ```
+    fn capitalize_ext(&self) -> Self
+    where
+        Self: Sized,
+    {
+        let string: &str = self.borrow();
+        let mut result = Self::with_capacity(string.len());
+        let mut chars = string.chars();
+        if let Some(first) = chars.next() {
+            for ch in first.to_uppercase() {
+                result.push(ch);
+            }
+            result.push_str(chars.as_str());
+        }
+        result
+    }
+
+    /// Returns a copy of this string in titlecase: the first character of
+    /// each whitespace-separated word is mapped to its Unicode titlecase form
+    /// and the remaining characters are lowercased.
+    ///
+    /// Titlecase differs from uppercase for some characters, such as the
+    /// digraph `ǆ` (titlecase `ǅ`, uppercase `Ǆ`) and `ß` (titlecase `Ss`).
+    ///
+    /// This method requires the `unicode-case-mapping` feature.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use inlinable_string::{InlinableString, StringExt};
+    ///
+    /// let s = InlinableString::from("ǆungla HOUSE");
+    /// assert_eq!(s.to_titlecase_ext(), "ǅungla House");
+    /// ```
+    #[cfg(feature = "unicode-case-mapping")]
+    fn to_titlecase_ext(&self) -> Self
+    where
+        Self: Sized,
+    {
+        let string: &str = self.borrow();
+        let mut result = Self::with_capacity(string.len());
+        let mut at_word_start = true;
+        for ch in string.chars() {
+            if ch.is_whitespace() {
+                result.push(ch);
+                at_word_start = true;
+            } else if at_word_start {
+                match unicode_case_mapping::to_titlecase(ch) {
+                    [0, 0, 0] => result.push(ch),
+                    mapping => {
+                        for &code in mapping.iter().take_while(|&&code| code != 0) {
+                            result.push(char::from_u32(code).expect("valid titlecase mapping"));
+                        }
+                    }
+                }
+                at_word_start = false;
+            } else {
+                for lower in ch.to_lowercase() {
+                    result.push(lower);
+                }
+            }
+        }
+        result
+    }
 }
 
 impl<'a> StringExt<'a> for String {
@@ -511,6 +1062,11 @@ impl<'a> StringExt<'a> for String {
         String::into_bytes(self)
     }
 
+    #[inline]
+    fn into_boxed_str(self) -> Box<str> {
+        String::into_boxed_str(self)
+    }
+
     #[inline]
     fn push_str(&mut self, string: &str) {
         String::push_str(self, string)
@@ -531,11 +1087,31 @@ impl<'a> StringExt<'a> for String {
         String::reserve_exact(self, additional)
     }
 
+    #[inline]
+    fn try_reserve(&mut self, additional: usize) -> Result<(), TryReserveError> {
+        String::try_reserve(self, additional)
+    }
+
+    #[inline]
+    fn try_reserve_exact(&mut self, additional: usize) -> Result<(), TryReserveError> {
+        String::try_reserve_exact(self, additional)
+    }
+
     #[inline]
     fn shrink_to_fit(&mut self) {
         String::shrink_to_fit(self)
     }
 
+    #[inline]
+    fn shrink_to(&mut self, min_capacity: usize) {
+        String::shrink_to(self, min_capacity)
+    }
+
+    #[inline]
+    fn repeat_inlinable(&self, n: usize) -> Self {
+        str::repeat(self, n)
+    }
+
     #[inline]
     fn push(&mut self, ch: char) {
         String::push(self, ch)
@@ -580,6 +1156,21 @@ impl<'a> StringExt<'a> for String {
     fn len(&self) -> usize {
         String::len(self)
     }
+
+    #[inline]
+    fn drain<R: RangeBounds<usize>>(&mut self, range: R) -> Drain<'_> {
+        Drain::heap(String::drain(self, range))
+    }
+
+    #[inline]
+    fn extend_from_within<R: RangeBounds<usize>>(&mut self, src: R) {
+        // `String::extend_from_within` needs Rust 1.87, so copy the bytes
+        // through the vector instead.
+        let src = drain::resolve_range(self, src);
+        // Safe because the range lies on character boundaries, so the copied
+        // bytes are valid UTF-8.
+        unsafe { self.as_mut_vec().extend_from_within(src) }
+    }
 }
 
 #[cfg(test)]
@@ -587,8 +1178,29 @@ mod std_string_stringext_sanity_tests {
     // Sanity tests for std::string::String's StringExt implementation.
 
     use alloc::string::String;
+    use alloc::borrow::Cow;
+    use alloc::vec::Vec;
     use super::StringExt;
 
+    #[test]
+    fn test_trait_object() {
+        use crate::InlinableString;
+        use alloc::boxed::Box;
+        use alloc::string::ToString;
+        use alloc::vec;
+
+        let mut strings: Vec<Box<dyn StringExt<'static>>> = vec![
+            Box::new(String::from("heap")),
+            Box::new(InlinableString::from("inline")),
+        ];
+        for s in &mut strings {
+            s.insert_str(0, "an ");
+            s.truncate(s.len() - 1);
+        }
+        assert_eq!(strings[0].to_string(), "an hea");
+        assert_eq!(strings[1].to_string(), "an inlin");
+    }
+
     #[test]
     fn test_new() {
         let s = <String as StringExt>::new();
@@ -628,6 +1240,14 @@ mod std_string_stringext_sanity_tests {
         assert_eq!(bytes, [104, 101, 108, 108, 111]);
     }
 
+    #[test]
+    fn test_into_boxed_str() {
+        let mut s = String::with_capacity(100);
+        s.push_str("hello");
+        let boxed = StringExt::into_boxed_str(s);
+        assert_eq!(&*boxed, "hello");
+    }
+
     #[test]
     fn test_push_str() {
         let mut s = String::from("hello");
@@ -655,6 +1275,14 @@ mod std_string_stringext_sanity_tests {
         assert!(String::capacity(&s) >= 100);
     }
 
+    #[test]
+    fn test_try_reserve() {
+        let mut s = String::new();
+        assert!(StringExt::try_reserve(&mut s, 10).is_ok());
+        assert!(s.capacity() >= 10);
+        assert!(StringExt::try_reserve_exact(&mut s, usize::MAX).is_err());
+    }
+
     #[test]
     fn test_shrink_to_fit() {
         let mut s = <String as StringExt>::with_capacity(100);
@@ -663,6 +1291,13 @@ mod std_string_stringext_sanity_tests {
         assert_eq!(String::capacity(&s), 3);
     }
 
+    #[test]
+    fn test_shrink_to() {
+        let mut s = String::with_capacity(100);
+        StringExt::shrink_to(&mut s, 10);
+        assert!(s.capacity() >= 10 && s.capacity() < 100);
+    }
+
     #[test]
     fn test_push() {
         let mut s = String::new();
@@ -685,4 +1320,99 @@ mod std_string_stringext_sanity_tests {
         assert_eq!(StringExt::pop(&mut s), Some('f'));
         assert_eq!(StringExt::pop(&mut s), None);
     }
+
+    #[test]
+    fn test_extend_from_within() {
+        let mut s = String::from("héllo");
+        StringExt::extend_from_within(&mut s, 1..3);
+        assert_eq!(s, "hélloé");
+    }
+
+    #[test]
+    fn test_char_access() {
+        let s = String::from("a€");
+        assert_eq!(StringExt::char_at(&s, 0), Some('a'));
+        assert_eq!(StringExt::char_at(&s, 1), Some('€'));
+        assert_eq!(StringExt::char_at(&s, 2), None);
+        assert_eq!(StringExt::nth_char(&s, 1), Some('€'));
+        assert_eq!(StringExt::nth_char(&s, 2), None);
+    }
+
+    #[test]
+    fn test_extend_from_slices() {
+        let mut s = String::from("a");
+        StringExt::extend_from_slices(&mut s, &["b", "", "cd"]);
+        assert_eq!(s, "abcd");
+        StringExt::extend_from_slices(&mut s, &[]);
+        assert_eq!(s, "abcd");
+    }
+
+    #[test]
+    fn test_count_matches() {
+        let s = String::from("a.b.c");
+        assert_eq!(StringExt::count_matches(&s, "."), 2);
+        assert_eq!(StringExt::count_matches(&s, ""), 6);
+    }
+
+    #[test]
+    fn test_contains_any() {
+        let s = String::from("hello");
+        assert!(StringExt::contains_any(&s, &["x", "ll"]));
+        assert!(!StringExt::contains_any(&s, &["x", "y"]));
+    }
+
+    #[test]
+    fn test_apply() {
+        let mut s = String::with_capacity(16);
+        s.push_str("hello");
+        let ptr = s.as_ptr();
+
+        assert!(!StringExt::apply(&mut s, |s| Cow::Borrowed(s)));
+        assert_eq!(s, "hello");
+        assert!(StringExt::apply(&mut s, |s| Cow::Owned(s.to_uppercase())));
+        assert_eq!(s, "HELLO");
+        assert_eq!(s.as_ptr(), ptr);
+    }
+
+    #[test]
+    fn test_expand_tabs() {
+        let mut s = String::from("\tx\n12345\ty\tz");
+        StringExt::expand_tabs(&mut s, 4);
+        assert_eq!(s, "    x\n12345   y   z");
+
+        let mut s = String::from("a\tb");
+        StringExt::expand_tabs(&mut s, 0);
+        assert_eq!(s, "ab");
+    }
+
+    #[test]
+    fn test_truncate_chars() {
+        let mut s = String::from("añb€c");
+        StringExt::truncate_chars(&mut s, 2);
+        assert_eq!(s, "añ");
+        StringExt::truncate_chars(&mut s, 0);
+        assert_eq!(s, "");
+    }
+
+    #[test]
+    fn test_repeat() {
+        let s = String::from("ab");
+        assert_eq!(s.repeat_inlinable(3), "ababab");
+        assert_eq!(s.repeat_inlinable(0), "");
+    }
+
+    #[test]
+    fn test_capitalize_ext() {
+        assert_eq!(StringExt::capitalize_ext(&String::from("hello World")), "Hello World");
+        assert_eq!(StringExt::capitalize_ext(&String::from("ß")), "SS");
+        assert_eq!(StringExt::capitalize_ext(&String::new()), "");
+    }
+
+    #[cfg(feature = "unicode-case-mapping")]
+    #[test]
+    fn test_to_titlecase_ext() {
+        let s = String::from("straße  ǆEMAL\tfoo");
+        assert_eq!(StringExt::to_titlecase_ext(&s), "Straße  ǅemal\tFoo");
+        assert_eq!(StringExt::to_titlecase_ext(&String::from("ßa")), "Ssa");
+    }
 }
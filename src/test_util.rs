@@ -0,0 +1,201 @@
+// Copyright 2015, The inlinable_string crate Developers. See the COPYRIGHT file
+// at the top-level directory of this distribution.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license <LICENSE-MIT
+// or http://opensource.org/licenses/MIT>, at your option. This file may not be
+// copied, modified, or distributed except according to those terms.
+
+//! Utilities for model-testing `StringExt` implementations against
+//! `std::string::String`.
+//!
+//! An `Op` is a single `StringExt` mutation. With `arbitrary`, a fuzzer or
+//! property test can generate sequences of them and `check_ops` replays a
+//! sequence on both a fresh `S` and a fresh `String`, asserting that their
+//! contents agree after every step.
+//!
+//! Byte indices carried by an `Op` are clamped to the string's length and
+//! rounded down to a character boundary when applied, so every generated
+//! sequence is valid for any string.
+//!
+//! This module requires the `test-util` feature and is not available with
+//! `no_std`.
+//!
+//! # Examples
+//!
+//! ```
+//! use arbitrary::{Arbitrary, Unstructured};
+//! use inlinable_string::InlinableString;
+//! use inlinable_string::test_util::{check_ops, Op};
+//!
+//! let data = [7u8; 256];
+//! let mut u = Unstructured::new(&data);
+//! let ops = Vec::<Op>::arbitrary(&mut u).unwrap();
+//! check_ops::<InlinableString>(&ops);
+//! ```
+
+use crate::StringExt;
+use alloc::string::String;
+use arbitrary::Arbitrary;
+
+/// A single `StringExt` mutation.
+///
+/// See the [module level documentation](./index.html) for more.
+#[derive(Arbitrary, Clone, Debug, PartialEq, Eq)]
+pub enum Op {
+    /// `push_str` the given string.
+    PushStr(String),
+    /// `push` the given character.
+    Push(char),
+    /// `insert` the given character at a byte index.
+    Insert(usize, char),
+    /// `insert_str` the given string at a byte index.
+    InsertStr(usize, String),
+    /// `remove` the character at a byte index. Does nothing to an empty
+    /// string.
+    Remove(usize),
+    /// Remove the characters in a range of byte indices.
+    RemoveRange(usize, usize),
+    /// Discard everything from a byte index onwards, as `String::split_off`
+    /// does to the string it is called on.
+    SplitOff(usize),
+    /// `truncate` to a byte index.
+    Truncate(usize),
+    /// `pop` the last character.
+    Pop,
+    /// `clear` the string.
+    Clear,
+    /// `reserve` additional capacity.
+    Reserve(u16),
+    /// `shrink_to_fit` the string.
+    ShrinkToFit,
+}
+
+impl Op {
+    /// Applies this operation to `string`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use inlinable_string::InlinableString;
+    /// use inlinable_string::test_util::Op;
+    ///
+    /// let mut s = InlinableString::from("héllo");
+    /// // Index 2 is inside 'é', so it is rounded down to 1.
+    /// Op::RemoveRange(2, 4).apply(&mut s);
+    /// assert_eq!(s, "hlo");
+    /// ```
+    pub fn apply<'a, S: StringExt<'a>>(&self, string: &mut S) {
+        match *self {
+            Op::PushStr(ref s) => string.push_str(s),
+            Op::Push(ch) => string.push(ch),
+            Op::Insert(idx, ch) => {
+                let idx = char_boundary(string, idx);
+                string.insert(idx, ch);
+            }
+            Op::InsertStr(idx, ref s) => {
+                let idx = char_boundary(string, idx);
+                string.insert_str(idx, s);
+            }
+            Op::Remove(idx) => {
+                let idx = char_boundary(string, idx);
+                if idx < string.len() {
+                    string.remove(idx);
+                }
+            }
+            Op::RemoveRange(start, end) => {
+                let start = char_boundary(string, start);
+                let end = char_boundary(string, end).max(start);
+                let mut removed = 0;
+                while removed < end - start {
+                    removed += string.remove(start).len_utf8();
+                }
+            }
+            Op::SplitOff(idx) | Op::Truncate(idx) => {
+                let idx = char_boundary(string, idx);
+                string.truncate(idx);
+            }
+            Op::Pop => {
+                string.pop();
+            }
+            Op::Clear => string.clear(),
+            Op::Reserve(additional) => string.reserve(usize::from(additional)),
+            Op::ShrinkToFit => string.shrink_to_fit(),
+        }
+    }
+}
+
+/// Clamps `idx` to the length of `string` and rounds it down to the nearest
+/// character boundary.
+fn char_boundary<'a, S: StringExt<'a>>(string: &S, idx: usize) -> usize {
+    let string: &str = string.borrow();
+    let mut idx = idx.min(string.len());
+    while !string.is_char_boundary(idx) {
+        idx -= 1;
+    }
+    idx
+}
+
+/// Applies `ops` in order to both a new `S` and a new `String`, panicking if
+/// their contents ever differ.
+///
+/// # Examples
+///
+/// ```
+/// use inlinable_string::InlinableString;
+/// use inlinable_string::test_util::{check_ops, Op};
+///
+/// check_ops::<InlinableString>(&[
+///     Op::PushStr("a string long enough to be stored on the heap".into()),
+///     Op::Truncate(5),
+///     Op::ShrinkToFit,
+///     Op::Insert(0, 'é'),
+/// ]);
+/// ```
+pub fn check_ops<'a, S: StringExt<'a>>(ops: &[Op]) {
+    let mut string = S::new();
+    let mut model = String::new();
+    for (i, op) in ops.iter().enumerate() {
+        op.apply(&mut string);
+        op.apply(&mut model);
+        let actual: &str = string.borrow();
+        assert_eq!(actual, &model[..], "after op {}: {:?}", i, op);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{check_ops, Op};
+    use crate::InlinableString;
+    use alloc::vec::Vec;
+    use arbitrary::{Arbitrary, Unstructured};
+
+    #[test]
+    fn test_arbitrary_ops() {
+        let mut seed = 0x2545_f491_4f6c_dd1du64;
+        for _ in 0..64 {
+            let data: Vec<u8> = (0..512)
+                .map(|_| {
+                    seed ^= seed << 13;
+                    seed ^= seed >> 7;
+                    seed ^= seed << 17;
+                    seed as u8
+                })
+                .collect();
+            let mut u = Unstructured::new(&data);
+            let ops = Vec::<Op>::arbitrary(&mut u).unwrap();
+            check_ops::<InlinableString>(&ops);
+        }
+    }
+
+    #[test]
+    fn test_boundaries() {
+        let mut s = InlinableString::from("aé");
+        Op::Insert(2, 'b').apply(&mut s);
+        assert_eq!(s, "abé");
+        Op::Remove(100).apply(&mut s);
+        assert_eq!(s, "abé");
+        Op::SplitOff(3).apply(&mut s);
+        assert_eq!(s, "ab");
+    }
+}
@@ -0,0 +1,226 @@
+// Copyright 2015, The inlinable_string crate Developers. See the COPYRIGHT file
+// at the top-level directory of this distribution.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license <LICENSE-MIT
+// or http://opensource.org/licenses/MIT>, at your option. This file may not be
+// copied, modified, or distributed except according to those terms.
+
+//! A trait for the operations that bounded and growable strings share, with
+//! the growing ones made fallible.
+//!
+//! `InlineString` cannot implement `StringExt`, because it cannot grow past
+//! its capacity. `TryStringExt` is implemented by `InlineString`, `String`
+//! and `InlinableString` alike, so code written against it works with all
+//! three. For `String` and `InlinableString`, which grow as needed, the
+//! fallible methods always succeed.
+//!
+//! # Examples
+//!
+//! ```
+//! use inlinable_string::{InlinableString, InlineString, TryStringExt};
+//! use inlinable_string::inline_string::NotEnoughSpaceError;
+//!
+//! fn tag<S: TryStringExt + ?Sized>(s: &mut S, tag: &str) -> Result<(), NotEnoughSpaceError> {
+//!     s.try_insert(0, '[')?;
+//!     s.try_insert_str(1, tag)?;
+//!     s.try_insert_str(1 + tag.len(), "] ")
+//! }
+//!
+//! let mut bounded = InlineString::from("disk full");
+//! tag(&mut bounded, "warn").unwrap();
+//! assert_eq!(bounded, "[warn] disk full");
+//!
+//! let mut growable = InlinableString::from("a message that lives on the heap");
+//! tag(&mut growable, "info").unwrap();
+//! assert_eq!(growable, "[info] a message that lives on the heap");
+//!
+//! let mut full = InlineString::from("x".repeat(InlineString::CAPACITY).as_str());
+//! assert!(tag(&mut full, "err").is_err());
+//! ```
+
+use crate::inline_string::NotEnoughSpaceError;
+use crate::{InlinableString, InlineString, StringExt};
+use alloc::borrow::Borrow;
+use alloc::string::String;
+
+/// Fallible string operations shared by bounded and growable strings.
+///
+/// Each method fails with `NotEnoughSpaceError`, leaving the string
+/// unchanged, if the string cannot hold the result. The string's contents
+/// can be read through the `Borrow<str>` supertrait. The trait is object
+/// safe, so `&mut dyn TryStringExt` works too.
+///
+/// See the [module level documentation](./index.html) for more.
+pub trait TryStringExt: Borrow<str> {
+    /// Appends a character to the end of this string.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use inlinable_string::{InlineString, TryStringExt};
+    ///
+    /// let mut s = InlineString::from("ab");
+    /// s.try_push('c').unwrap();
+    /// assert_eq!(s, "abc");
+    /// ```
+    fn try_push(&mut self, ch: char) -> Result<(), NotEnoughSpaceError>;
+
+    /// Appends a string slice to the end of this string.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use inlinable_string::{InlineString, TryStringExt};
+    ///
+    /// let mut s = InlineString::from("ab");
+    /// s.try_push_str("cd").unwrap();
+    /// assert_eq!(s, "abcd");
+    /// assert!(s.try_push_str(&"x".repeat(InlineString::CAPACITY)).is_err());
+    /// assert_eq!(s, "abcd");
+    /// ```
+    fn try_push_str(&mut self, string: &str) -> Result<(), NotEnoughSpaceError>;
+
+    /// Inserts a character into this string at byte position `idx`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `idx` is larger than the string's length, or if it does not
+    /// lie on a character boundary.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use inlinable_string::{InlineString, TryStringExt};
+    ///
+    /// let mut s = InlineString::from("ac");
+    /// s.try_insert(1, 'b').unwrap();
+    /// assert_eq!(s, "abc");
+    /// ```
+    fn try_insert(&mut self, idx: usize, ch: char) -> Result<(), NotEnoughSpaceError>;
+
+    /// Inserts a string slice into this string at byte position `idx`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `idx` is larger than the string's length, or if it does not
+    /// lie on a character boundary.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use inlinable_string::{InlineString, TryStringExt};
+    ///
+    /// let mut s = InlineString::from("ad");
+    /// s.try_insert_str(1, "bc").unwrap();
+    /// assert_eq!(s, "abcd");
+    /// ```
+    fn try_insert_str(&mut self, idx: usize, string: &str) -> Result<(), NotEnoughSpaceError>;
+}
+
+impl TryStringExt for InlineString {
+    #[inline]
+    fn try_push(&mut self, ch: char) -> Result<(), NotEnoughSpaceError> {
+        self.push(ch)
+    }
+
+    #[inline]
+    fn try_push_str(&mut self, string: &str) -> Result<(), NotEnoughSpaceError> {
+        self.push_str(string)
+    }
+
+    #[inline]
+    fn try_insert(&mut self, idx: usize, ch: char) -> Result<(), NotEnoughSpaceError> {
+        assert!(
+            self.is_char_boundary(idx),
+            "inlinable_string: index is not on a character boundary"
+        );
+        self.insert(idx, ch)
+    }
+
+    #[inline]
+    fn try_insert_str(&mut self, idx: usize, string: &str) -> Result<(), NotEnoughSpaceError> {
+        assert!(
+            self.is_char_boundary(idx),
+            "inlinable_string: index is not on a character boundary"
+        );
+        self.insert_str(idx, string)
+    }
+}
+
+macro_rules! impl_growable {
+    ($ty:ty) => {
+        impl TryStringExt for $ty {
+            #[inline]
+            fn try_push(&mut self, ch: char) -> Result<(), NotEnoughSpaceError> {
+                StringExt::push(self, ch);
+                Ok(())
+            }
+
+            #[inline]
+            fn try_push_str(&mut self, string: &str) -> Result<(), NotEnoughSpaceError> {
+                StringExt::push_str(self, string);
+                Ok(())
+            }
+
+            #[inline]
+            fn try_insert(&mut self, idx: usize, ch: char) -> Result<(), NotEnoughSpaceError> {
+                StringExt::insert(self, idx, ch);
+                Ok(())
+            }
+
+            #[inline]
+            fn try_insert_str(
+                &mut self,
+                idx: usize,
+                string: &str,
+            ) -> Result<(), NotEnoughSpaceError> {
+                StringExt::insert_str(self, idx, string);
+                Ok(())
+            }
+        }
+    };
+}
+
+impl_growable!(String);
+impl_growable!(InlinableString);
+
+#[cfg(test)]
+mod tests {
+    use super::TryStringExt;
+    use crate::{InlinableString, InlineString};
+    use alloc::string::String;
+
+    fn exercise(s: &mut dyn TryStringExt) -> String {
+        s.try_push_str("añ").unwrap();
+        s.try_push('c').unwrap();
+        s.try_insert(1, 'b').unwrap();
+        s.try_insert_str(0, "<").unwrap();
+        let contents: &str = (*s).borrow();
+        String::from(contents)
+    }
+
+    #[test]
+    fn test_implementors_agree() {
+        assert_eq!(exercise(&mut InlineString::new()), "<abñc");
+        assert_eq!(exercise(&mut InlinableString::new()), "<abñc");
+        assert_eq!(exercise(&mut String::new()), "<abñc");
+    }
+
+    #[test]
+    fn test_inline_string_full() {
+        let mut s = InlineString::from("x".repeat(InlineString::CAPACITY).as_str());
+        assert!(s.try_push('y').is_err());
+        assert!(s.try_push_str("y").is_err());
+        assert!(s.try_insert(0, 'y').is_err());
+        assert!(s.try_insert_str(0, "y").is_err());
+        assert_eq!(s.len(), InlineString::CAPACITY);
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_inline_string_insert_not_char_boundary() {
+        let mut s = InlineString::from("é");
+        let _ = s.try_insert(1, 'x');
+    }
+}
@@ -0,0 +1,204 @@
+// Copyright 2015, The inlinable_string crate Developers. See the COPYRIGHT file
+// at the top-level directory of this distribution.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license <LICENSE-MIT
+// or http://opensource.org/licenses/MIT>, at your option. This file may not be
+// copied, modified, or distributed except according to those terms.
+
+//! Helpers for framing strings on the wire as a varint byte length followed by
+//! the string's UTF-8 bytes.
+//!
+//! The length is encoded as an unsigned LEB128 varint, so strings shorter than
+//! 128 bytes cost a single byte of framing. When decoding, frames that fit in
+//! `InlinableString::INLINE_CAPACITY` are read directly into inline storage
+//! and never touch the heap.
+//!
+//! # Examples
+//!
+//! ```
+//! use inlinable_string::wire::{decode_len_prefixed, encode_len_prefixed};
+//!
+//! let mut buf = Vec::new();
+//! encode_len_prefixed(&mut buf, "hello").unwrap();
+//! assert_eq!(buf, b"\x05hello");
+//!
+//! let s = decode_len_prefixed(&mut &buf[..]).unwrap();
+//! assert_eq!(s, "hello");
+//! ```
+
+use crate::{InlinableString, InlineString};
+use std::io::{self, Read, Write};
+use std::str;
+
+/// The maximum number of bytes in a LEB128-encoded `u64`.
+const MAX_VARINT_LEN: usize = 10;
+
+/// Writes `string` to `writer` as a varint byte length followed by its UTF-8
+/// bytes.
+///
+/// # Examples
+///
+/// ```
+/// use inlinable_string::wire::encode_len_prefixed;
+///
+/// let mut buf = Vec::new();
+/// encode_len_prefixed(&mut buf, "hi").unwrap();
+/// assert_eq!(buf, [2, b'h', b'i']);
+/// ```
+pub fn encode_len_prefixed<W: Write + ?Sized>(writer: &mut W, string: &str) -> io::Result<()> {
+    let mut header = [0; MAX_VARINT_LEN];
+    let mut header_len = 0;
+    let mut len = string.len() as u64;
+    loop {
+        let byte = (len & 0x7f) as u8;
+        len >>= 7;
+        if len == 0 {
+            header[header_len] = byte;
+            header_len += 1;
+            break;
+        }
+        header[header_len] = byte | 0x80;
+        header_len += 1;
+    }
+
+    writer.write_all(&header[..header_len])?;
+    writer.write_all(string.as_bytes())
+}
+
+/// Reads a string framed by `encode_len_prefixed` from `reader`.
+///
+/// Frames no longer than `InlinableString::INLINE_CAPACITY` bytes are decoded
+/// directly into an `InlinableString::Inline`; longer frames are read into a
+/// heap-allocated `String`.
+///
+/// # Errors
+///
+/// Returns an error of kind `InvalidData` if the length prefix is malformed or
+/// the frame is not valid UTF-8, and an error of kind `UnexpectedEof` if the
+/// reader ends before the frame is complete. Any error returned by `reader` is
+/// passed through.
+///
+/// # Examples
+///
+/// ```
+/// use inlinable_string::wire::decode_len_prefixed;
+///
+/// let mut frames: &[u8] = b"\x03foo\x03bar";
+/// assert_eq!(decode_len_prefixed(&mut frames).unwrap(), "foo");
+/// assert_eq!(decode_len_prefixed(&mut frames).unwrap(), "bar");
+/// assert!(decode_len_prefixed(&mut frames).is_err());
+/// ```
+pub fn decode_len_prefixed<R: Read + ?Sized>(reader: &mut R) -> io::Result<InlinableString> {
+    let len = read_varint(reader)?;
+
+    if len <= InlineString::CAPACITY as u64 {
+        let len = len as usize;
+        let mut bytes = [0; InlineString::CAPACITY];
+        reader.read_exact(&mut bytes[..len])?;
+        let string = str::from_utf8(&bytes[..len]).map_err(invalid_data)?;
+        return Ok(InlinableString::Inline(InlineString::from(string)));
+    }
+
+    // Don't trust the length prefix with an up-front allocation: let the
+    // buffer grow as bytes actually arrive.
+    let mut bytes = Vec::new();
+    Read::take(&mut *reader, len).read_to_end(&mut bytes)?;
+    if (bytes.len() as u64) < len {
+        return Err(io::Error::new(
+            io::ErrorKind::UnexpectedEof,
+            "inlinable_string: length-prefixed frame is truncated",
+        ));
+    }
+    String::from_utf8(bytes)
+        .map(InlinableString::Heap)
+        .map_err(invalid_data)
+}
+
+fn read_varint<R: Read + ?Sized>(reader: &mut R) -> io::Result<u64> {
+    let mut value = 0u64;
+    for i in 0..MAX_VARINT_LEN {
+        let mut byte = [0];
+        reader.read_exact(&mut byte)?;
+        let byte = byte[0];
+
+        let shift = 7 * i as u32;
+        let bits = u64::from(byte & 0x7f);
+        if bits << shift >> shift != bits {
+            break;
+        }
+        value |= bits << shift;
+
+        if byte & 0x80 == 0 {
+            return Ok(value);
+        }
+    }
+
+    Err(invalid_data("inlinable_string: length prefix overflows a u64"))
+}
+
+fn invalid_data<E>(error: E) -> io::Error
+where
+    E: Into<Box<dyn std::error::Error + Send + Sync>>,
+{
+    io::Error::new(io::ErrorKind::InvalidData, error)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{decode_len_prefixed, encode_len_prefixed};
+    use crate::{InlinableString, InlineString};
+    use std::io::ErrorKind;
+
+    #[test]
+    fn test_round_trip() {
+        let long = "a".repeat(300);
+        let mut buf = Vec::new();
+        encode_len_prefixed(&mut buf, "").unwrap();
+        encode_len_prefixed(&mut buf, "small").unwrap();
+        encode_len_prefixed(&mut buf, &long).unwrap();
+
+        // 300 needs two bytes of varint.
+        assert_eq!(buf.len(), 1 + (1 + 5) + (2 + 300));
+
+        let mut reader = &buf[..];
+        assert_eq!(decode_len_prefixed(&mut reader).unwrap(), "");
+        let small = decode_len_prefixed(&mut reader).unwrap();
+        assert!(matches!(small, InlinableString::Inline(_)));
+        assert_eq!(small, "small");
+        let decoded = decode_len_prefixed(&mut reader).unwrap();
+        assert!(matches!(decoded, InlinableString::Heap(_)));
+        assert_eq!(decoded, &long[..]);
+        assert!(reader.is_empty());
+    }
+
+    #[test]
+    fn test_inline_boundary() {
+        let fits = "a".repeat(InlineString::CAPACITY);
+        let mut buf = Vec::new();
+        encode_len_prefixed(&mut buf, &fits).unwrap();
+        let decoded = decode_len_prefixed(&mut &buf[..]).unwrap();
+        assert!(matches!(decoded, InlinableString::Inline(_)));
+        assert_eq!(decoded, &fits[..]);
+    }
+
+    #[test]
+    fn test_errors() {
+        let truncated: &[u8] = b"\x05hel";
+        let err = decode_len_prefixed(&mut &truncated[..]).unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::UnexpectedEof);
+
+        let mut truncated_long = vec![0xac, 0x02];
+        truncated_long.extend_from_slice(&[b'a'; 100]);
+        let err = decode_len_prefixed(&mut &truncated_long[..]).unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::UnexpectedEof);
+
+        let invalid_utf8: &[u8] = b"\x02\xc3\x28";
+        let err = decode_len_prefixed(&mut &invalid_utf8[..]).unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::InvalidData);
+
+        let overflow: &[u8] = &[0xff; 11];
+        let err = decode_len_prefixed(&mut &overflow[..]).unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::InvalidData);
+    }
+}
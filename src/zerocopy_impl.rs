@@ -0,0 +1,88 @@
+use crate::InlineString;
+use core::mem;
+use core::str;
+
+impl InlineString {
+    /// Reinterprets `bytes` as an `InlineString` without copying, if they
+    /// hold a valid one: exactly `size_of::<InlineString>()` bytes, where the
+    /// first byte is a length no greater than `InlineString::CAPACITY`, the
+    /// content bytes up to that length are valid UTF-8, and the remaining
+    /// content bytes are zero. Returns `None` otherwise.
+    ///
+    /// This is the checked inverse of the `zerocopy::IntoBytes` view, for
+    /// reading strings back out of memory-mapped or serialized structs.
+    ///
+    /// This method requires the `zerocopy` feature.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use inlinable_string::InlineString;
+    /// use zerocopy::IntoBytes;
+    ///
+    /// let s = InlineString::from("hello");
+    /// // `InlineString::as_bytes` is the string's contents; use the trait
+    /// // method for the raw layout.
+    /// let raw = IntoBytes::as_bytes(&s);
+    /// assert_eq!(raw[0], 5);
+    /// assert_eq!(InlineString::try_ref_from_bytes(raw), Some(&s));
+    ///
+    /// let mut corrupt = raw.to_vec();
+    /// corrupt[0] = 200;
+    /// assert_eq!(InlineString::try_ref_from_bytes(&corrupt), None);
+    /// ```
+    pub fn try_ref_from_bytes(bytes: &[u8]) -> Option<&InlineString> {
+        if bytes.len() != mem::size_of::<InlineString>() {
+            return None;
+        }
+        let (&length, content) = bytes.split_first()?;
+        let length = length as usize;
+        if length > InlineString::CAPACITY
+            || str::from_utf8(&content[..length]).is_err()
+            || content[length..].iter().any(|&b| b != 0)
+        {
+            return None;
+        }
+        // `InlineString` is `repr(C)` with only `u8` fields, so it has
+        // alignment one and no padding, and `bytes` upholds its invariants.
+        Some(unsafe { &*(bytes.as_ptr() as *const InlineString) })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::InlineString;
+    use alloc::vec::Vec;
+    use core::mem;
+    use zerocopy::IntoBytes;
+
+    #[test]
+    fn test_layout() {
+        assert_eq!(mem::size_of::<InlineString>(), InlineString::CAPACITY + 1);
+        assert_eq!(mem::align_of::<InlineString>(), 1);
+
+        let mut s = InlineString::from("abcdef");
+        s.truncate(2);
+        let mut expected = Vec::from(&b"\x02ab"[..]);
+        expected.resize(InlineString::CAPACITY + 1, 0);
+        assert_eq!(IntoBytes::as_bytes(&s), &expected[..]);
+    }
+
+    #[test]
+    fn test_try_ref_from_bytes() {
+        let s = InlineString::from("añb");
+        let raw = IntoBytes::as_bytes(&s).to_vec();
+        assert_eq!(InlineString::try_ref_from_bytes(&raw), Some(&s));
+        assert_eq!(InlineString::try_ref_from_bytes(&raw[1..]), None);
+
+        // Length splits a character.
+        let mut bad = raw.clone();
+        bad[0] = 2;
+        assert_eq!(InlineString::try_ref_from_bytes(&bad), None);
+
+        // Non-zero byte past the end.
+        let mut bad = raw;
+        bad[InlineString::CAPACITY] = b'x';
+        assert_eq!(InlineString::try_ref_from_bytes(&bad), None);
+    }
+}